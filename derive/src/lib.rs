@@ -1,6 +1,6 @@
 use darling::FromDeriveInput;
 use proc_macro::TokenStream;
-use syn::{parse_macro_input, Data, DeriveInput};
+use syn::{parse_macro_input, Data, DeriveInput, Fields};
 
 #[macro_use]
 extern crate quote;
@@ -10,10 +10,24 @@ extern crate proc_macro;
 #[darling(default, attributes(relay))]
 struct RelayNodeObjectAttributes {
     node_suffix: Option<String>,
+    key: Option<syn::Type>,
+    id_format: Option<String>,
+    codec: Option<syn::Path>,
+    legacy_id_input: bool,
+}
+
+#[derive(FromDeriveInput, Default)]
+#[darling(default, attributes(relay))]
+struct RelayInterfaceAttributes {
+    id_format: Option<String>,
+    codec: Option<syn::Path>,
+    max_nodes: Option<usize>,
+    dataloader: bool,
+    cache: bool,
 }
 
 /// The RelayNodeObject macro is applied to a type to automatically implement the RelayNodeStruct trait.
-/// ```
+/// ```ignore
 /// #[derive(SimpleObject, RelayNodeObject)] // See the 'RelayNodeObject' derive macro
 /// #[graphql(complex)]
 /// #[relay(node_suffix = "u")] // This controls the 'RelayNodeObject' macro. In this case the prefix is shortened to 'u', the default is in the name of the struct.
@@ -23,6 +37,9 @@ struct RelayNodeObjectAttributes {
 ///     pub role: String,
 /// }
 /// ```
+/// The nodes key is a UUID by default. Any type implementing the 'RelayNodeKey' trait can be used instead with `#[relay(key = "i64")]`, in which case the relay ID's use the 'Base64Codec' unless another format is provided. The 'suffix' format can't be used with a custom key.
+/// The format of the relay ID's can be changed using `#[relay(id_format = "base64")]` or `#[relay(codec = "MyCodec")]`. It MUST match the format of every interface enum the object is part of.
+/// Using `#[relay(legacy_id_input)]` makes the objects 'RelayNodeID' also accept the bare key as input, see 'RelayNodeStruct::LEGACY_ID_INPUT'.
 #[proc_macro_derive(RelayNodeObject, attributes(relay))]
 pub fn derive_relay_node_object(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as syn::DeriveInput);
@@ -40,56 +57,229 @@ pub fn derive_relay_node_object(input: TokenStream) -> TokenStream {
         ident.to_string()
     };
 
+    // The 'SuffixCodec' relies on the 32 character UUID so other keys default to the 'Base64Codec'.
+    if attrs.key.is_some() && attrs.id_format.as_deref() == Some("suffix") {
+        panic!("The 'key' option of the 'RelayNodeObject' macro can't be used with `id_format = \"suffix\"` as the 'SuffixCodec' requires a UUID key!");
+    }
+    let default_codec: syn::Path = match attrs.key {
+        Some(_) => syn::parse_quote! { async_graphql_relay::Base64Codec },
+        None => syn::parse_quote! { async_graphql_relay::SuffixCodec },
+    };
+    let codec = codec_path("RelayNodeObject", attrs.codec, attrs.id_format.as_deref())
+        .unwrap_or(default_codec);
+
+    let key = match attrs.key {
+        Some(key) => quote! { #key },
+        None => quote! { async_graphql_relay::uuid::Uuid },
+    };
+    let legacy_id_input = attrs.legacy_id_input.then(|| {
+        quote! {
+            const LEGACY_ID_INPUT: bool = true;
+        }
+    });
+
     quote! {
         impl async_graphql_relay::RelayNodeStruct for #ident {
             const ID_SUFFIX: &'static str = #value;
+            type Key = #key;
+            type Codec = #codec;
+            #legacy_id_input
         }
     }
     .into()
 }
 
+/// codec_path resolves the 'codec' and 'id_format' options of a macro into the path of the codec, if either was provided.
+fn codec_path(
+    macro_name: &str,
+    codec: Option<syn::Path>,
+    id_format: Option<&str>,
+) -> Option<syn::Path> {
+    match (codec, id_format) {
+        (Some(_), Some(_)) => panic!(
+            "The 'codec' and 'id_format' options of the '{}' macro can't be used together!",
+            macro_name
+        ),
+        (Some(codec), None) => Some(codec),
+        (None, None) => None,
+        (None, Some("suffix")) => Some(syn::parse_quote! { async_graphql_relay::SuffixCodec }),
+        (None, Some("base64")) => Some(syn::parse_quote! { async_graphql_relay::Base64Codec }),
+        (None, Some(format)) => panic!(
+            "Unknown 'id_format' '{}' provided to the '{}' macro! Expected 'suffix' or 'base64'.",
+            format, macro_name
+        ),
+    }
+}
+
 /// The RelayInterface macro is applied to a GraphQL Interface enum to allow it to be used for Relay's node query.
 /// This enum should contain all types that that exist in your GraphQL schema to work as designed in the Relay server specification.
-/// ```
+/// Each variant holds a single object implementing 'RelayNode'. The same object can be part of many interface enums, e.g. an internal and a public schema, as 'RelayNode::get' returns the object itself and it is converted into the enum using the 'From' implementations generated by the async-graphql 'Interface' macro.
+/// ```ignore
 /// #[derive(Interface, RelayInterface)] // See the 'RelayInterface' derive macro
-/// #[graphql(field(name = "id", type = "NodeGlobalID"))] // The 'RelayInterface' macro generates a type called '{enum_name}GlobalID' which should be used like this to facilitate using the async_graphql_relay::RelayNodeID for globally unique ID's
+/// #[graphql(field(name = "id", ty = "NodeGlobalID"))] // The 'RelayInterface' macro generates a type called '{enum_name}GlobalID' which should be used like this to facilitate using the async_graphql_relay::RelayNodeID for globally unique ID's
 /// pub enum Node {
 ///     User(User),
 ///     Tenant(Tenant),
 ///    // Put all of your Object's in this enum
 /// }
 /// ```
-#[proc_macro_derive(RelayInterface)]
+/// The format of the relay ID's is the one used by the objects, which must all use the same format. It can be set using `#[relay(id_format = "base64")]`, which encodes them as an opaque base64url token instead of the default UUID followed by the 'node_suffix'.
+/// A custom format can be used by implementing the 'RelayIdCodec' trait and passing it using `#[relay(codec = "MyCodec")]`. The objects must use the same options on the 'RelayNodeObject' macro.
+/// The '{enum_name}GlobalID' type can be used as an argument to accept the ID of any node. It can be converted into the RelayNodeID of a specific type using 'TryFrom'.
+/// The macro also generates a '{enum_name}TypedID' enum, with a variant holding the RelayNodeID of each type, which can be created from a '{enum_name}GlobalID' using 'TryFrom' and matched on to handle each type.
+/// The number of ID's accepted by 'fetch_nodes' defaults to 100 and can be changed using `#[relay(max_nodes = 50)]`.
+/// Using `#[relay(dataloader)]` makes 'fetch_node' use the 'RelayNodeLoader' stored in the RelayContext so the nodes fetched within a request are batched. This requires the 'dataloader' feature and every node to implement 'Clone'.
+/// Using `#[relay(cache)]` makes 'fetch_node' reuse the nodes already fetched within a request using the 'RelayNodeCache' stored in the RelayContext or request data. This requires the interface enum to implement 'Clone'.
+#[proc_macro_derive(RelayInterface, attributes(relay))]
 pub fn derive_relay_interface(input: TokenStream) -> TokenStream {
-    let DeriveInput { ident, data, .. } = parse_macro_input!(input);
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    let attrs = RelayInterfaceAttributes::from_derive_input(&input)
+        .expect("Error parsing 'RelayInterface' macro options!");
+    let DeriveInput {
+        ident: interface_ident,
+        data,
+        ..
+    } = input;
 
-    let ident = format_ident!("{}GlobalID", ident);
+    let codec = codec_path("RelayInterface", attrs.codec, attrs.id_format.as_deref());
+
+    let max_nodes = attrs.max_nodes.map(|max_nodes| {
+        quote! {
+            const MAX_NODES: usize = #max_nodes;
+        }
+    });
+
+    let ident = format_ident!("{}GlobalID", interface_ident);
+    let typed_ident = format_ident!("{}TypedID", interface_ident);
     let impls;
+    let typed_variants;
+    let typed_matchers;
+    let typed_to_global;
     let node_matchers;
+    let ctx_node_matchers;
+    let group_idents;
+    let group_matchers;
+    let variant_types;
     if let Data::Enum(data) = &data {
-        impls = data.variants.iter().map(|variant| {
+        variant_types = data
+            .variants
+            .iter()
+            .map(|variant| match &variant.fields {
+                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => fields.unnamed[0].ty.clone(),
+                _ => panic!(
+                    "The variant '{}' must hold a single object to be used with the 'RelayInterface' macro!",
+                    variant.ident
+                ),
+            })
+            .collect::<Vec<_>>();
+
+        impls = data.variants.iter().zip(&variant_types).map(|(variant, variant_ty)| {
             let variant_ident = &variant.ident;
             quote! {
-                impl std::convert::From<&async_graphql_relay::RelayNodeID<#variant_ident>> for #ident {
-                    fn from(t: &async_graphql_relay::RelayNodeID<#variant_ident>) -> Self {
+                /// The conversion panics if the codec can't encode the ID. Use the 'to_global_id' method of the typed ID to handle the error.
+                impl std::convert::From<&async_graphql_relay::RelayNodeID<#variant_ty>> for #ident {
+                    fn from(t: &async_graphql_relay::RelayNodeID<#variant_ty>) -> Self {
                         #ident(String::from(t))
                     }
                 }
+
+                impl std::convert::TryFrom<&#ident> for async_graphql_relay::RelayNodeID<#variant_ty> {
+                    type Error = async_graphql_relay::RelayError;
+
+                    fn try_from(t: &#ident) -> Result<Self, Self::Error> {
+                        async_graphql_relay::RelayNodeID::<#variant_ty>::new_from_relay_id(t.0.clone())
+                    }
+                }
+
+                impl std::convert::From<async_graphql_relay::RelayNodeID<#variant_ty>> for #typed_ident {
+                    fn from(t: async_graphql_relay::RelayNodeID<#variant_ty>) -> Self {
+                        #typed_ident::#variant_ident(t)
+                    }
+                }
+            }
+        });
+
+        typed_variants = data
+            .variants
+            .iter()
+            .zip(&variant_types)
+            .map(|(variant, variant_ty)| {
+                let variant_ident = &variant.ident;
+                quote! {
+                    #variant_ident(async_graphql_relay::RelayNodeID<#variant_ty>)
+                }
+            });
+
+        typed_matchers = data.variants.iter().zip(&variant_types).map(|(variant, variant_ty)| {
+            let variant_ident = &variant.ident;
+            quote! {
+                <#variant_ty as async_graphql_relay::RelayNodeStruct>::ID_SUFFIX => {
+                    async_graphql_relay::RelayNodeID::<#variant_ty>::new_from_relay_id(t.0.clone())
+                        .map(#typed_ident::#variant_ident)
+                }
             }
         });
 
-        node_matchers = data.variants.iter().map(|variant| {
+        typed_to_global = data.variants.iter().map(|variant| {
             let variant_ident = &variant.ident;
             quote! {
-                <#variant_ident as async_graphql_relay::RelayNodeStruct>::ID_SUFFIX => {
-                    <#variant_ident as async_graphql_relay::RelayNode>::get(
-                        ctx,
-                        async_graphql_relay::RelayNodeID::<#variant_ident>::new_from_relay_id(
-                            relay_id.to_string(),
-                        )?,
-                    )
-                    .await?
-                    .ok_or_else(|| async_graphql::Error::new("A node with the specified id could not be found!"))
+                #typed_ident::#variant_ident(id) => id.to_relay_id().map(#ident)
+            }
+        });
+
+        let dataloader = attrs.dataloader;
+        ctx_node_matchers = variant_types.iter().map(move |variant_ty| {
+            let get = if dataloader {
+                quote! { async_graphql_relay::__private::load_node_ctx::<#variant_ty> }
+            } else {
+                quote! { <#variant_ty as async_graphql_relay::RelayNode>::get_ctx }
+            };
+            quote! {
+                <#variant_ty as async_graphql_relay::RelayNodeStruct>::ID_SUFFIX => {
+                    let id = async_graphql_relay::RelayNodeID::<#variant_ty>::new_from_relay_id(relay_id)
+                        .map_err(async_graphql_relay::__private::relay_error)?;
+                    let observer = async_graphql_relay::__private::NodeObserver::new(async_graphql_relay::__private::StatsHandle::from_ctx(ctx), &id);
+                    observer.observe(async move {
+                        async_graphql_relay::__private::authorize_node_ctx(ctx, &id).await?;
+                        #get(ctx, id).await.map_err(async_graphql_relay::__private::backend_error)
+                    })
+                    .await
+                    .map(|node| node.map(<Self as std::convert::From<#variant_ty>>::from))
+                }
+            }
+        });
+
+        node_matchers = variant_types.iter().map(move |variant_ty| {
+            let get = if dataloader {
+                quote! { async_graphql_relay::__private::load_node::<#variant_ty> }
+            } else {
+                quote! { <#variant_ty as async_graphql_relay::RelayNode>::get }
+            };
+            quote! {
+                <#variant_ty as async_graphql_relay::RelayNodeStruct>::ID_SUFFIX => {
+                    let id = async_graphql_relay::RelayNodeID::<#variant_ty>::new_from_relay_id(relay_id)
+                        .map_err(async_graphql_relay::__private::relay_error)?;
+                    let observer = async_graphql_relay::__private::NodeObserver::new(async_graphql_relay::__private::StatsHandle::from_relay_ctx(&ctx), &id);
+                    observer.observe(async move {
+                        async_graphql_relay::__private::authorize_node(ctx.clone(), &id).await?;
+                        #get(ctx, id).await.map_err(async_graphql_relay::__private::backend_error)
+                    })
+                    .await
+                    .map(|node| node.map(<Self as std::convert::From<#variant_ty>>::from))
+                }
+            }
+        });
+
+        group_idents = (0..data.variants.len())
+            .map(|i| format_ident!("group_{}", i))
+            .collect::<Vec<_>>();
+        group_matchers = variant_types.iter().zip(&group_idents).map(|(variant_ty, group_ident)| {
+            quote! {
+                <#variant_ty as async_graphql_relay::RelayNodeStruct>::ID_SUFFIX => {
+                    match async_graphql_relay::RelayNodeID::<#variant_ty>::new_from_relay_id(relay_id) {
+                        Ok(id) => #group_ident.push((index, id)),
+                        Err(err) => results[index] = Err(async_graphql_relay::__private::relay_error(err)),
+                    }
                 }
             }
         });
@@ -97,16 +287,212 @@ pub fn derive_relay_interface(input: TokenStream) -> TokenStream {
         panic!("The 'RelayNodeObject' macro can only be used on enums!");
     }
 
+    let codec = match (codec, variant_types.first()) {
+        (Some(codec), _) => quote! { #codec },
+        (None, Some(variant_ty)) => {
+            quote! { <#variant_ty as async_graphql_relay::RelayNodeStruct>::Codec }
+        }
+        (None, None) => quote! { async_graphql_relay::SuffixCodec },
+    };
+
+    // group_ids checks the number of ID's and sorts them into a group per type for 'fetch_nodes' and 'fetch_nodes_ctx'.
+    let group_ids = quote! {
+        let max_nodes = <Self as async_graphql_relay::RelayNodeInterface>::MAX_NODES;
+        if relay_ids.len() > max_nodes {
+            return Err(async_graphql::ErrorExtensions::extend(&async_graphql_relay::RelayError::TooManyIds { max: max_nodes }));
+        }
+
+        let mut results: Vec<Result<Option<Self>, async_graphql::Error>> = Vec::with_capacity(relay_ids.len());
+        #(let mut #group_idents: Vec<(usize, async_graphql_relay::RelayNodeID<#variant_types>)> = Vec::new();)*
+        for (index, relay_id) in relay_ids.into_iter().enumerate() {
+            results.push(Ok(None));
+            let suffix = match <<Self as async_graphql_relay::RelayNodeInterface>::Codec as async_graphql_relay::RelayIdCodec>::decode(&relay_id) {
+                Ok((suffix, _)) => suffix,
+                Err(err) => {
+                    results[index] = Err(async_graphql_relay::__private::relay_error(err));
+                    continue;
+                }
+            };
+            match suffix.as_str() {
+                #(#group_matchers)*
+                _ => results[index] = Err(async_graphql_relay::__private::relay_error(async_graphql_relay::RelayError::UnknownType(suffix))),
+            }
+        }
+    };
+    let dataloader = attrs.dataloader;
+    let get_many_ctx = variant_types.iter().map(|variant_ty| {
+        if dataloader {
+            quote! { async_graphql_relay::__private::load_nodes_ctx::<#variant_ty> }
+        } else {
+            quote! { <#variant_ty as async_graphql_relay::RelayNode>::get_many_ctx }
+        }
+    });
+
+    let fetch_node_body = quote! {
+        let (suffix, _) = <<Self as async_graphql_relay::RelayNodeInterface>::Codec as async_graphql_relay::RelayIdCodec>::decode(&relay_id)
+            .map_err(async_graphql_relay::__private::relay_error)?;
+        match suffix.as_str() {
+            #(#node_matchers)*
+            _ => Err(async_graphql_relay::__private::relay_error(async_graphql_relay::RelayError::UnknownType(suffix))),
+        }
+    };
+    let fetch_node_ctx_body = quote! {
+        let (suffix, _) = <<Self as async_graphql_relay::RelayNodeInterface>::Codec as async_graphql_relay::RelayIdCodec>::decode(&relay_id)
+            .map_err(async_graphql_relay::__private::relay_error)?;
+        match suffix.as_str() {
+            #(#ctx_node_matchers)*
+            _ => Err(async_graphql_relay::__private::relay_error(async_graphql_relay::RelayError::UnknownType(suffix))),
+        }
+    };
+    let (fetch_node_body, fetch_node_ctx_body) = if attrs.cache {
+        (
+            quote! {
+                let cache = ctx.get::<async_graphql_relay::RelayNodeCache>().cloned();
+                let stats = async_graphql_relay::__private::StatsHandle::from_relay_ctx(&ctx);
+                let selection = ctx.get::<async_graphql_relay::RelayNodeSelection>().cloned();
+                let cache_key = relay_id.clone();
+                async_graphql_relay::__private::cached_node(cache, stats, &cache_key, selection, async move { #fetch_node_body }).await
+            },
+            quote! {
+                let cache = ctx.data_opt::<async_graphql_relay::RelayNodeCache>().cloned();
+                let stats = async_graphql_relay::__private::StatsHandle::from_ctx(ctx);
+                let selection = Some(async_graphql_relay::RelayNodeSelection::from_ctx(ctx));
+                let cache_key = relay_id.clone();
+                async_graphql_relay::__private::cached_node(cache, stats, &cache_key, selection, async move { #fetch_node_ctx_body }).await
+            },
+        )
+    } else {
+        (fetch_node_body, fetch_node_ctx_body)
+    };
+    let fetch_node_body = quote! {
+        let span = async_graphql_relay::__private::fetch_node_span(&relay_id);
+        async_graphql_relay::__private::traced(span, async move { #fetch_node_body }).await
+    };
+    let fetch_node_ctx_body = quote! {
+        let span = async_graphql_relay::__private::fetch_node_span(&relay_id);
+        async_graphql_relay::__private::traced(span, async move { #fetch_node_ctx_body }).await
+    };
+
+    let serde_impls = if cfg!(feature = "serde") {
+        quote! {
+            impl async_graphql_relay::__private::serde::Serialize for #ident {
+                fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: async_graphql_relay::__private::serde::Serializer,
+                {
+                    serializer.serialize_str(&self.0)
+                }
+            }
+
+            impl<'de> async_graphql_relay::__private::serde::Deserialize<'de> for #ident {
+                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: async_graphql_relay::__private::serde::Deserializer<'de>,
+                {
+                    let id = #ident(<String as async_graphql_relay::__private::serde::Deserialize>::deserialize(deserializer)?);
+                    <#typed_ident as std::convert::TryFrom<&#ident>>::try_from(&id)
+                        .map_err(<D::Error as async_graphql_relay::__private::serde::de::Error>::custom)?;
+                    Ok(id)
+                }
+            }
+
+            impl async_graphql_relay::__private::serde::Serialize for #typed_ident {
+                fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: async_graphql_relay::__private::serde::Serializer,
+                {
+                    let id = self.to_global_id().map_err(<S::Error as async_graphql_relay::__private::serde::ser::Error>::custom)?;
+                    async_graphql_relay::__private::serde::Serialize::serialize(&id, serializer)
+                }
+            }
+
+            impl<'de> async_graphql_relay::__private::serde::Deserialize<'de> for #typed_ident {
+                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: async_graphql_relay::__private::serde::Deserializer<'de>,
+                {
+                    let id = <#ident as async_graphql_relay::__private::serde::Deserialize>::deserialize(deserializer)?;
+                    <#typed_ident as std::convert::TryFrom<#ident>>::try_from(id)
+                        .map_err(<D::Error as async_graphql_relay::__private::serde::de::Error>::custom)
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let ident_doc = format!(
+        "{} is the relay ID of any node in the '{}' interface.\n\nThe 'From' conversions into it panic when the codec can't encode the ID, e.g. the 'SignedCodec' or 'EncryptedCodec' when the 'RelayKeys' aren't available. Use '{}::to_global_id' to handle the error instead.",
+        ident, interface_ident, typed_ident
+    );
+    let typed_ident_doc = format!(
+        "{} holds the RelayNodeID of a node in the '{}' interface so each type can be handled using a match.",
+        typed_ident, interface_ident
+    );
+
     quote! {
-                #[derive(Clone, Debug)]
+        #[doc = #ident_doc]
+        #[derive(Clone, Debug)]
         pub struct #ident(String);
 
+        #[doc = #typed_ident_doc]
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        pub enum #typed_ident {
+            #(#typed_variants),*
+        }
+
+        impl #typed_ident {
+            /// to_global_id encodes the ID, returning an error when the codec can't encode it.
+            pub fn to_global_id(&self) -> Result<#ident, async_graphql_relay::RelayError> {
+                match self {
+                    #(#typed_to_global),*
+                }
+            }
+        }
+
         #(#impls)*
 
+        impl std::convert::TryFrom<&#ident> for #typed_ident {
+            type Error = async_graphql_relay::RelayError;
+
+            fn try_from(t: &#ident) -> Result<Self, Self::Error> {
+                let (suffix, _) = <<#interface_ident as async_graphql_relay::RelayNodeInterface>::Codec as async_graphql_relay::RelayIdCodec>::decode(&t.0)?;
+                match suffix.as_str() {
+                    #(#typed_matchers)*
+                    _ => Err(async_graphql_relay::RelayError::UnknownType(suffix)),
+                }
+            }
+        }
+
+        impl std::convert::TryFrom<#ident> for #typed_ident {
+            type Error = async_graphql_relay::RelayError;
+
+            fn try_from(t: #ident) -> Result<Self, Self::Error> {
+                <#typed_ident as std::convert::TryFrom<&#ident>>::try_from(&t)
+            }
+        }
+
+        #serde_impls
+
+        impl std::convert::From<#typed_ident> for #ident {
+            fn from(t: #typed_ident) -> Self {
+                t.to_global_id()
+                    .unwrap_or_else(|err| panic!("Failed to encode the relay ID: {}", err))
+            }
+        }
+
         #[async_graphql::Scalar(name = "ID")]
         impl async_graphql::ScalarType for #ident {
             fn parse(value: async_graphql::Value) -> async_graphql::InputValueResult<Self> {
-                unimplemented!();
+                match value {
+                    async_graphql::Value::String(relay_id) => {
+                        let id = #ident(relay_id);
+                        <#typed_ident as std::convert::TryFrom<&#ident>>::try_from(&id)
+                            .map_err(async_graphql_relay::RelayError::into_input_value_error)?;
+                        Ok(id)
+                    }
+                    _ => Err(async_graphql::InputValueError::expected_type(value)),
+                }
             }
 
             fn to_value(&self) -> async_graphql::Value {
@@ -114,20 +500,48 @@ pub fn derive_relay_interface(input: TokenStream) -> TokenStream {
             }
         }
 
-        impl async_graphql_relay::RelayNodeInterface for Node {
-            async fn fetch_node(ctx: async_graphql_relay::RelayContext, relay_id: String) -> Result<Self, async_graphql::Error> {
-                if relay_id.len() < 32 {
-                    return Err(async_graphql::Error::new("Invalid id provided to node query!"));
-                }
-                let (_, suffix) = relay_id.split_at(32);
-                match suffix {
-                    #(#node_matchers)*
-                    _ => Err(async_graphql::Error::new("A node with the specified id could not be found!")),
-                }
+        const _: fn() = || {
+            #(async_graphql_relay::__private::assert_codec::<#variant_types, <#interface_ident as async_graphql_relay::RelayNodeInterface>::Codec>();)*
+        };
+
+        impl async_graphql_relay::RelayNodeInterface for #interface_ident {
+            type Codec = #codec;
+            #max_nodes
+
+            async fn fetch_node(ctx: async_graphql_relay::RelayContext, relay_id: String) -> Result<Option<Self>, async_graphql::Error> {
+                #fetch_node_body
             }
-        }
+
+            async fn fetch_node_ctx(ctx: &async_graphql::Context<'_>, relay_id: String) -> Result<Option<Self>, async_graphql::Error> {
+                #fetch_node_ctx_body
             }
+
+            async fn fetch_nodes(ctx: async_graphql_relay::RelayContext, relay_ids: Vec<String>) -> Result<Vec<Result<Option<Self>, async_graphql::Error>>, async_graphql::Error> {
+                let span = async_graphql_relay::__private::fetch_nodes_span(relay_ids.len());
+                async_graphql_relay::__private::traced(span, async move {
+                    #group_ids
+                    let groups = vec![#(async_graphql_relay::__private::fetch_node_group::<#variant_types, Self>(ctx.clone(), #group_idents)),*];
+                    for (index, result) in async_graphql_relay::__private::join_all(groups).await.into_iter().flatten() {
+                        results[index] = result;
+                    }
+                    Ok(results)
+                })
+                .await
+            }
+
+            async fn fetch_nodes_ctx(ctx: &async_graphql::Context<'_>, relay_ids: Vec<String>) -> Result<Vec<Result<Option<Self>, async_graphql::Error>>, async_graphql::Error> {
+                let span = async_graphql_relay::__private::fetch_nodes_span(relay_ids.len());
+                async_graphql_relay::__private::traced(span, async move {
+                    #group_ids
+                    let groups = vec![#(async_graphql_relay::__private::fetch_node_group_ctx::<#variant_types, Self, _, _>(ctx, #group_idents, #get_many_ctx)),*];
+                    for (index, result) in async_graphql_relay::__private::join_all(groups).await.into_iter().flatten() {
+                        results[index] = result;
+                    }
+                    Ok(results)
+                })
+                .await
+            }
+        }
+    }
     .into()
 }
-
-// TODO: Unit tests
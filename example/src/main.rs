@@ -4,14 +4,18 @@ use actix_web::guard;
 use actix_web::web::Data;
 use actix_web::{web, App, HttpResponse, HttpServer, Responder};
 use async_graphql::http::{playground_source, GraphQLPlaygroundConfig};
-use async_graphql::{EmptyMutation, EmptySubscription, Error, Interface, Object};
+use async_graphql::{EmptyMutation, EmptySubscription, Interface, MergedObject, Object};
 use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse};
-use async_graphql_relay::{RelayContext, RelayInterface, RelayNodeID, RelayNodeInterface};
+use async_graphql_relay::{RelayContext, RelayInterface, RelayNodeID, RelayNodeQuery};
 
 mod tenant;
 mod user;
 
-pub struct QueryRoot;
+#[derive(Default)]
+pub struct Query;
+
+#[derive(MergedObject, Default)]
+pub struct QueryRoot(Query, RelayNodeQuery<Node>); // 'RelayNodeQuery' adds the 'node' and 'nodes' queries for the 'Node' interface.
 
 #[derive(Interface, RelayInterface)]
 #[graphql(field(name = "id", ty = "NodeGlobalID"))] // The 'NodeGlobalID' type comes from the 'RelayInterface' macro.
@@ -21,7 +25,7 @@ pub enum Node {
 }
 
 #[Object]
-impl QueryRoot {
+impl Query {
     async fn user(&self) -> User {
         User {
             id: RelayNodeID::new_from_str("92ba0c2d-4b4e-4e29-91dd-8f96a078c3ff").unwrap(),
@@ -37,14 +41,6 @@ impl QueryRoot {
             description: "Testing123".to_string(),
         }
     }
-
-    async fn node(
-        &self,
-        #[graphql(validator(min_length = 33, max_length = 33))] id: String, // Ensure the length's of the longest 'node_suffix' plus 32 is validated.
-    ) -> Result<Node, Error> {
-        let ctx = RelayContext::new::<String>("Hello World".to_string()); // This could include your database connection and/or any other context required in your implementations of the 'RelayNode' trait.
-        Node::fetch_node(ctx, id).await
-    }
 }
 
 pub type Schema = async_graphql::Schema<QueryRoot, EmptyMutation, EmptySubscription>;
@@ -61,7 +57,9 @@ pub async fn playground() -> impl Responder {
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    let schema = Schema::build(QueryRoot, EmptyMutation, EmptySubscription).finish();
+    let schema = Schema::build(QueryRoot::default(), EmptyMutation, EmptySubscription)
+        .data(RelayContext::new::<String>("Hello World".to_string())) // This could include your database connection and/or any other context required in your implementations of the 'RelayNode' trait. It is passed to 'RelayNode::get' by the 'node' and 'nodes' queries.
+        .finish();
 
     println!("Listening http://localhost:8080/ ...");
     HttpServer::new(move || {
@@ -1,8 +1,6 @@
 use async_graphql::{Error, SimpleObject};
 use async_graphql_relay::{RelayContext, RelayNode, RelayNodeID, RelayNodeObject};
 
-use crate::Node;
-
 #[derive(Debug, SimpleObject, RelayNodeObject)]
 #[relay(node_suffix = "t")]
 pub struct Tenant {
@@ -12,19 +10,19 @@ pub struct Tenant {
 }
 
 impl RelayNode for Tenant {
-    type TNode = Node;
-
-    async fn get(ctx: RelayContext, id: RelayNodeID<Self>) -> Result<Option<Self::TNode>, Error> {
-        let ctx_str = ctx.get::<String>().unwrap();
+    async fn get(ctx: RelayContext, id: RelayNodeID<Self>) -> Result<Option<Self>, Error> {
+        let ctx_str = ctx.get_required::<String>()?;
         println!("Getting Tenant: {:?} with context {}", id, ctx_str);
 
-        Ok(Some(
-            Tenant {
-                id: RelayNodeID::new_from_str("92ba0c2d-4b4e-4e29-91dd-8f96a078c3ff").unwrap(),
-                name: "My Company".to_string(),
-                description: "Testing123".to_string(),
-            }
-            .into(),
-        ))
+        Ok(Some(Tenant {
+            id: RelayNodeID::new_from_str("92ba0c2d-4b4e-4e29-91dd-8f96a078c3ff").unwrap(),
+            name: "My Company".to_string(),
+            description: "Testing123".to_string(),
+        }))
+    }
+
+    async fn authorize(ctx: RelayContext, id: &RelayNodeID<Self>) -> Result<bool, Error> {
+        println!("Authorizing Tenant: {:?}", id);
+        Ok(ctx.get::<String>().is_some()) // This could check the tenant belongs to the current user before it is fetched.
     }
 }
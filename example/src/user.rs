@@ -1,8 +1,6 @@
 use async_graphql::{ComplexObject, Error, SimpleObject};
 use async_graphql_relay::{RelayContext, RelayNode, RelayNodeID, RelayNodeObject};
 
-use crate::Node;
-
 #[derive(Debug, SimpleObject, RelayNodeObject)]
 #[graphql(complex)]
 #[relay(node_suffix = "u")]
@@ -13,20 +11,15 @@ pub struct User {
 }
 
 impl RelayNode for User {
-    type TNode = Node;
-
-    async fn get(ctx: RelayContext, id: RelayNodeID<Self>) -> Result<Option<Self::TNode>, Error> {
-        let ctx_str = ctx.get::<String>().unwrap();
+    async fn get(ctx: RelayContext, id: RelayNodeID<Self>) -> Result<Option<Self>, Error> {
+        let ctx_str = ctx.get_required::<String>()?;
         println!("Getting User: {:?} with context {}", id, ctx_str);
 
-        Ok(Some(
-            User {
-                id: RelayNodeID::new_from_str("92ba0c2d-4b4e-4e29-91dd-8f96a078c3ff").unwrap(),
-                name: "Oscar".to_string(),
-                role: "Testing123".to_string(),
-            }
-            .into(),
-        ))
+        Ok(Some(User {
+            id: RelayNodeID::new_from_str("92ba0c2d-4b4e-4e29-91dd-8f96a078c3ff").unwrap(),
+            name: "Oscar".to_string(),
+            role: "Testing123".to_string(),
+        }))
     }
 }
 
@@ -0,0 +1,63 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+use async_graphql::Error;
+
+use crate::RelayNodeSelection;
+
+type CacheKey = (TypeId, String, Option<RelayNodeSelection>);
+type CacheEntry = Arc<futures_util::lock::Mutex<Option<Arc<dyn Any + Send + Sync>>>>;
+
+/// RelayNodeCache is a request scoped cache of the nodes fetched by 'fetch_node', keyed by their relay ID and 'RelayNodeSelection', so a node which is refetched several times within a request only reaches 'RelayNode::get' once.
+/// Fields selecting different fields of the same node are fetched separately so a node which only fetches the selected fields is never reused by another field.
+/// A new cache should be created for every request and stored in the request data when using 'fetch_node_ctx' or in the RelayContext passed to 'fetch_node'. The interface enum must use `#[relay(cache)]` on the 'RelayInterface' macro.
+/// Concurrent fetches of the same ID wait for the first one to finish. Nodes which can't be found are cached but errors are not.
+/// ```ignore
+/// schema.execute(Request::new(query).data(RelayNodeCache::new())).await
+/// ```
+#[derive(Clone, Default)]
+pub struct RelayNodeCache(Arc<Mutex<HashMap<CacheKey, CacheEntry>>>);
+
+impl RelayNodeCache {
+    /// new creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) async fn get_or_fetch<N, F>(
+        &self,
+        relay_id: &str,
+        selection: Option<RelayNodeSelection>,
+        fetch: F,
+        on_hit: impl FnOnce(),
+    ) -> Result<Option<N>, Error>
+    where
+        N: Clone + Send + Sync + 'static,
+        F: Future<Output = Result<Option<N>, Error>>,
+    {
+        let entry = self
+            .0
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .entry((TypeId::of::<N>(), relay_id.to_string(), selection))
+            .or_default()
+            .clone();
+
+        let mut cached = entry.lock().await;
+        if let Some(node) = cached
+            .as_ref()
+            .and_then(|node| node.downcast_ref::<Option<N>>())
+        {
+            on_hit();
+            return Ok(node.clone());
+        }
+
+        let node = fetch.await?;
+        *cached = Some(Arc::new(node.clone()));
+        Ok(node)
+    }
+}
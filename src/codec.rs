@@ -0,0 +1,57 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+use crate::RelayError;
+
+/// RelayIdCodec defines how a type tag (the objects 'ID_SUFFIX') and a key (the objects ID) are turned into the globally unique relay ID which is exposed to clients and back again.
+/// The codec is selected per object using the 'RelayNodeObject' macro, e.g. `#[relay(codec = "MyCodec")]`, and every object in an interface enum must use the codec of the interface.
+/// Implement this trait to use a custom relay ID format.
+pub trait RelayIdCodec {
+    /// TAMPER_PROOF is true for codecs which verify the relay ID's they decode, like the 'SignedCodec' and 'EncryptedCodec'.
+    /// The scalar never accepts the bare key of an object using a tamper proof codec, even when `#[relay(legacy_id_input)]` is used.
+    const TAMPER_PROOF: bool = false;
+
+    /// encode creates a relay ID from the type tag and the key.
+    fn encode(type_tag: &str, key: &str) -> Result<String, RelayError>;
+
+    /// decode splits a relay ID back into the type tag and the key.
+    fn decode(relay_id: &str) -> Result<(String, String), RelayError>;
+}
+
+fn invalid_id() -> RelayError {
+    RelayError::InvalidFormat("Invalid id provided to node query!".to_string())
+}
+
+/// SuffixCodec is the default codec. The 32 character UUID is followed by the objects 'ID_SUFFIX', e.g. '92ba0c2d4b4e4e2991dd8f96a078c3ffu'.
+pub struct SuffixCodec;
+
+impl RelayIdCodec for SuffixCodec {
+    fn encode(type_tag: &str, key: &str) -> Result<String, RelayError> {
+        Ok(format!("{}{}", key, type_tag))
+    }
+
+    fn decode(relay_id: &str) -> Result<(String, String), RelayError> {
+        if relay_id.len() <= 32 || !relay_id.is_char_boundary(32) {
+            return Err(invalid_id());
+        }
+        let (key, type_tag) = relay_id.split_at(32);
+        Ok((type_tag.to_string(), key.to_string()))
+    }
+}
+
+/// Base64Codec encodes the 'ID_SUFFIX' and the UUID as an opaque base64url token of 'ID_SUFFIX:uuid', e.g. 'VXNlcjo5MmJhMGMyZDRiNGU0ZTI5OTFkZDhmOTZhMDc4YzNmZg'.
+pub struct Base64Codec;
+
+impl RelayIdCodec for Base64Codec {
+    fn encode(type_tag: &str, key: &str) -> Result<String, RelayError> {
+        Ok(URL_SAFE_NO_PAD.encode(format!("{}:{}", type_tag, key)))
+    }
+
+    fn decode(relay_id: &str) -> Result<(String, String), RelayError> {
+        let decoded = URL_SAFE_NO_PAD
+            .decode(relay_id)
+            .map_err(|_err| invalid_id())?;
+        let decoded = String::from_utf8(decoded).map_err(|_err| invalid_id())?;
+        let (type_tag, key) = decoded.split_once(':').ok_or_else(invalid_id)?;
+        Ok((type_tag.to_string(), key.to_string()))
+    }
+}
@@ -0,0 +1,127 @@
+use std::marker::PhantomData;
+
+use aes_gcm_siv::{aead::Aead, Aes256GcmSiv, KeyInit, Nonce};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use sha2::{Digest, Sha256};
+
+use crate::{keyring::with_encryption_keys, RelayError, RelayIdCodec, SuffixCodec};
+
+/// EncryptedCodec wraps another codec and encrypts the relay ID's it creates using AES-256-GCM-SIV so the type and key of a node are never exposed to clients.
+/// A fixed nonce is used so an object always has the same relay ID, which Relay relies on to cache objects. GCM-SIV is resistant to nonce reuse so this only reveals whether two ID's are equal.
+/// The ciphertext is authenticated so ID's which have been modified by a client are rejected when they are decoded.
+/// The keys are provided by the 'RelayKeys' extension of the schema using 'RelayKeys::with_encryption_keys'.
+pub struct EncryptedCodec<C: RelayIdCodec = SuffixCodec>(PhantomData<C>);
+
+fn cipher(key: &[u8]) -> Aes256GcmSiv {
+    Aes256GcmSiv::new(&Sha256::digest(key))
+}
+
+impl<C: RelayIdCodec> RelayIdCodec for EncryptedCodec<C> {
+    const TAMPER_PROOF: bool = true;
+
+    fn encode(type_tag: &str, key: &str) -> Result<String, RelayError> {
+        let relay_id = C::encode(type_tag, key)?;
+        let ciphertext = with_encryption_keys(|keys| {
+            cipher(keys.primary_key()).encrypt(&Nonce::default(), relay_id.as_bytes())
+        })?
+        .map_err(|_err| {
+            RelayError::InvalidFormat("The relay ID could not be encrypted!".to_string())
+        })?;
+        Ok(URL_SAFE_NO_PAD.encode(ciphertext))
+    }
+
+    fn decode(relay_id: &str) -> Result<(String, String), RelayError> {
+        let invalid_id =
+            || RelayError::InvalidFormat("Invalid id provided to node query!".to_string());
+        let ciphertext = URL_SAFE_NO_PAD
+            .decode(relay_id)
+            .map_err(|_err| invalid_id())?;
+
+        let plaintext = with_encryption_keys(|keys| {
+            keys.keys().find_map(|key| {
+                cipher(key)
+                    .decrypt(&Nonce::default(), ciphertext.as_slice())
+                    .ok()
+            })
+        })?
+        .ok_or_else(|| {
+            RelayError::InvalidFormat("The provided id could not be decrypted!".to_string())
+        })?;
+        let relay_id = String::from_utf8(plaintext).map_err(|_err| invalid_id())?;
+
+        C::decode(&relay_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RelayKeyRing, RelayKeys};
+
+    const KEY: &str = "92ba0c2d4b4e4e2991dd8f96a078c3ff";
+
+    fn keys(keys: RelayKeyRing) -> RelayKeys {
+        RelayKeys::new().with_encryption_keys(keys)
+    }
+
+    fn encode(type_tag: &str) -> String {
+        EncryptedCodec::<SuffixCodec>::encode(type_tag, KEY).unwrap()
+    }
+
+    fn decode(relay_id: &str) -> Result<(String, String), RelayError> {
+        EncryptedCodec::<SuffixCodec>::decode(relay_id)
+    }
+
+    #[test]
+    fn round_trip() {
+        keys(RelayKeyRing::new("secret")).scope(|| {
+            let relay_id = encode("u");
+            assert!(!relay_id.contains(KEY));
+            assert_eq!(relay_id, encode("u"));
+            assert_ne!(relay_id, encode("t"));
+            assert_eq!(decode(&relay_id), Ok(("u".to_string(), KEY.to_string())));
+        });
+    }
+
+    #[test]
+    fn tampered_ciphertext() {
+        keys(RelayKeyRing::new("secret")).scope(|| {
+            let mut ciphertext = URL_SAFE_NO_PAD.decode(encode("u")).unwrap();
+            for index in [0, ciphertext.len() - 1] {
+                ciphertext[index] ^= 1;
+                let relay_id = URL_SAFE_NO_PAD.encode(&ciphertext);
+                assert!(matches!(
+                    decode(&relay_id),
+                    Err(RelayError::InvalidFormat(_))
+                ));
+                ciphertext[index] ^= 1;
+            }
+            assert!(matches!(
+                decode("not-base64!"),
+                Err(RelayError::InvalidFormat(_))
+            ));
+        });
+    }
+
+    #[test]
+    fn key_rotation() {
+        let old_id = keys(RelayKeyRing::new("old")).scope(|| encode("u"));
+        keys(RelayKeyRing::new("new").with_verification_key("old")).scope(|| {
+            assert_eq!(decode(&old_id), Ok(("u".to_string(), KEY.to_string())));
+            assert_ne!(encode("u"), old_id);
+        });
+        keys(RelayKeyRing::new("new")).scope(|| {
+            assert!(matches!(decode(&old_id), Err(RelayError::InvalidFormat(_))));
+        });
+    }
+
+    #[test]
+    fn missing_keys() {
+        let relay_id = keys(RelayKeyRing::new("secret")).scope(|| encode("u"));
+        assert_eq!(
+            EncryptedCodec::<SuffixCodec>::encode("u", KEY),
+            Err(RelayError::MissingKeys)
+        );
+        assert_eq!(decode(&relay_id), Err(RelayError::MissingKeys));
+    }
+}
@@ -0,0 +1,132 @@
+use std::fmt;
+
+use async_graphql::{Error, ErrorExtensions, InputType, InputValueError};
+
+use crate::trace::node_type;
+
+/// RelayError is returned when a relay ID can't be converted into a RelayNodeID or a node can't be fetched.
+/// It implements 'ErrorExtensions' so the GraphQL error has a stable 'code' extension which clients can use to tell the failures apart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RelayError {
+    /// InvalidFormat is returned when the relay ID couldn't be decoded by the codec or contains an invalid key.
+    InvalidFormat(String),
+    /// InvalidSignature is returned when the signature of a relay ID created by the 'SignedCodec' doesn't match, e.g. because the ID was modified by a client.
+    InvalidSignature,
+    /// MissingKeys is returned when a relay ID is encoded or decoded by the 'SignedCodec' or 'EncryptedCodec' while the 'RelayKeys' aren't available.
+    MissingKeys,
+    /// UnknownType is returned when the relay ID is valid but its 'ID_SUFFIX' doesn't belong to any type in the interface.
+    UnknownType(String),
+    /// TypeMismatch is returned when the relay ID is valid but belongs to a different type than the one requested.
+    TypeMismatch {
+        /// expected is the 'ID_SUFFIX' of the requested type.
+        expected: &'static str,
+        /// actual is the 'ID_SUFFIX' found in the relay ID.
+        actual: String,
+    },
+    /// TooManyIds is returned when more relay ID's than 'RelayNodeInterface::MAX_NODES' are passed to 'fetch_nodes'.
+    TooManyIds {
+        /// max is the maximum number of relay ID's accepted by the interface.
+        max: usize,
+    },
+    /// NotFound can be returned when a node with the relay ID doesn't exist. 'fetch_node' returns 'None' instead so the 'node' query returns null.
+    NotFound,
+    /// Unauthorized is returned when 'RelayNode::authorize' denies access to the node.
+    Unauthorized,
+    /// Backend is returned when fetching the node failed, e.g. because of a database error.
+    Backend(String),
+}
+
+impl RelayError {
+    /// code returns the value of the 'code' extension added to the GraphQL error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            RelayError::InvalidFormat(_) => "INVALID_FORMAT",
+            RelayError::InvalidSignature => "INVALID_SIGNATURE",
+            RelayError::MissingKeys => "MISSING_KEYS",
+            RelayError::UnknownType(_) => "UNKNOWN_TYPE",
+            RelayError::TypeMismatch { .. } => "TYPE_MISMATCH",
+            RelayError::TooManyIds { .. } => "TOO_MANY_IDS",
+            RelayError::NotFound => "NOT_FOUND",
+            RelayError::Unauthorized => "UNAUTHORIZED",
+            RelayError::Backend(_) => "BACKEND",
+        }
+    }
+
+    /// into_input_value_error converts the error into the error returned by a scalars parser, keeping the 'code' extension.
+    pub fn into_input_value_error<T: InputType>(self) -> InputValueError<T> {
+        let err = InputValueError::custom(&self).with_extension("code", self.code());
+        match self {
+            RelayError::UnknownType(actual) => err.with_extension("actual", actual),
+            RelayError::TypeMismatch { expected, actual } => err
+                .with_extension("expected", expected)
+                .with_extension("actual", actual),
+            _ => err,
+        }
+    }
+}
+
+impl fmt::Display for RelayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RelayError::InvalidFormat(message) => f.write_str(message),
+            RelayError::InvalidSignature => {
+                f.write_str("The signature of the provided id is invalid!")
+            }
+            RelayError::MissingKeys => f.write_str(
+                "No relay keys are available! Add the 'RelayKeys' extension when building your schema.",
+            ),
+            RelayError::UnknownType(actual) => {
+                write!(f, "Unknown type '{}' in the provided id!", actual)
+            }
+            RelayError::TypeMismatch { expected, actual } => write!(
+                f,
+                "Expected an id of type '{}' but got an id of type '{}'!",
+                expected, actual
+            ),
+            RelayError::TooManyIds { max } => write!(
+                f,
+                "A maximum of {} ids can be provided to the nodes query!",
+                max
+            ),
+            RelayError::NotFound => f.write_str("A node with the specified id could not be found!"),
+            RelayError::Unauthorized => f.write_str("You are not authorized to access this node!"),
+            RelayError::Backend(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for RelayError {}
+
+/// check_node_count returns a 'Backend' error when 'RelayNode::get_many' didn't return a result for every ID, as the nodes can't be matched to their ID's.
+pub(crate) fn check_node_count<T>(
+    ids: usize,
+    nodes: Vec<Option<T>>,
+) -> Result<Vec<Option<T>>, Error> {
+    if nodes.len() != ids {
+        return Err(RelayError::Backend(format!(
+            "'{}::get_many' returned {} nodes for {} ids!",
+            node_type::<T>(),
+            nodes.len(),
+            ids
+        ))
+        .extend());
+    }
+    Ok(nodes)
+}
+
+impl ErrorExtensions for RelayError {
+    fn extend(&self) -> Error {
+        Error::new(self.to_string()).extend_with(|_, extensions| {
+            extensions.set("code", self.code());
+            match self {
+                RelayError::UnknownType(actual) => extensions.set("actual", actual.as_str()),
+                RelayError::TypeMismatch { expected, actual } => {
+                    extensions.set("expected", *expected);
+                    extensions.set("actual", actual.as_str());
+                }
+                RelayError::TooManyIds { max } => extensions.set("max", *max as u64),
+                _ => {}
+            }
+        })
+    }
+}
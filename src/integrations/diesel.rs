@@ -0,0 +1,41 @@
+use diesel::{
+    backend::Backend,
+    deserialize::{self, FromSql},
+    serialize::{self, Output, ToSql},
+    sql_types::{BigInt, Integer, SmallInt, Text, Uuid},
+};
+
+use crate::{RelayNode, RelayNodeID};
+
+// RelayNodeID is stored as the nodes key. The 'AsExpression' and 'FromSqlRow' impls are derived on the RelayNodeID struct.
+// Only keys with a matching diesel SQL type (Uuid, BigInt, Integer, SmallInt and Text) are supported, so the unsigned integer keys can't be used with diesel.
+
+impl<T, ST, DB> FromSql<ST, DB> for RelayNodeID<T>
+where
+    T: RelayNode,
+    T::Key: FromSql<ST, DB>,
+    DB: Backend,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        T::Key::from_sql(bytes).map(RelayNodeID::new)
+    }
+}
+
+macro_rules! impl_to_sql {
+    ($($sql_type:ty),*) => {
+        $(
+            impl<T, DB> ToSql<$sql_type, DB> for RelayNodeID<T>
+            where
+                T: RelayNode,
+                T::Key: ToSql<$sql_type, DB>,
+                DB: Backend,
+            {
+                fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, DB>) -> serialize::Result {
+                    self.key().to_sql(out)
+                }
+            }
+        )*
+    };
+}
+
+impl_to_sql!(Uuid, BigInt, Integer, SmallInt, Text);
@@ -0,0 +1,6 @@
+#[cfg(feature = "diesel")]
+mod diesel;
+#[cfg(feature = "sea-orm")]
+mod sea_orm;
+#[cfg(feature = "sqlx")]
+mod sqlx;
@@ -0,0 +1,70 @@
+use sea_orm::{
+    sea_query::{ArrayType, ColumnType, Nullable, ValueType, ValueTypeErr},
+    ColIdx, DbErr, QueryResult, TryFromU64, TryGetError, TryGetable, Value,
+};
+
+use crate::{RelayNode, RelayNodeID};
+
+// RelayNodeID is stored as the nodes key so it can be used as the primary key of an entity.
+
+impl<T> From<RelayNodeID<T>> for Value
+where
+    T: RelayNode,
+    T::Key: Into<Value>,
+{
+    fn from(id: RelayNodeID<T>) -> Self {
+        id.into_key().into()
+    }
+}
+
+impl<T> TryGetable for RelayNodeID<T>
+where
+    T: RelayNode,
+    T::Key: TryGetable,
+{
+    fn try_get_by<I: ColIdx>(res: &QueryResult, index: I) -> Result<Self, TryGetError> {
+        T::Key::try_get_by(res, index).map(RelayNodeID::new)
+    }
+}
+
+impl<T> ValueType for RelayNodeID<T>
+where
+    T: RelayNode,
+    T::Key: ValueType,
+{
+    fn try_from(v: Value) -> Result<Self, ValueTypeErr> {
+        <T::Key as ValueType>::try_from(v).map(RelayNodeID::new)
+    }
+
+    fn type_name() -> String {
+        format!("RelayNodeID<{}>", T::Key::type_name())
+    }
+
+    fn array_type() -> ArrayType {
+        T::Key::array_type()
+    }
+
+    fn column_type() -> ColumnType {
+        T::Key::column_type()
+    }
+}
+
+impl<T> Nullable for RelayNodeID<T>
+where
+    T: RelayNode,
+    T::Key: Nullable,
+{
+    fn null() -> Value {
+        T::Key::null()
+    }
+}
+
+impl<T> TryFromU64 for RelayNodeID<T>
+where
+    T: RelayNode,
+    T::Key: TryFromU64,
+{
+    fn try_from_u64(n: u64) -> Result<Self, DbErr> {
+        T::Key::try_from_u64(n).map(RelayNodeID::new)
+    }
+}
@@ -0,0 +1,53 @@
+use sqlx::{encode::IsNull, error::BoxDynError, Database, Decode, Encode, Type};
+
+use crate::{RelayNode, RelayNodeID};
+
+// RelayNodeID is stored as the nodes key so it can be used with any database which supports the key type.
+
+impl<T, DB> Type<DB> for RelayNodeID<T>
+where
+    T: RelayNode,
+    T::Key: Type<DB>,
+    DB: Database,
+{
+    fn type_info() -> DB::TypeInfo {
+        T::Key::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        T::Key::compatible(ty)
+    }
+}
+
+impl<'q, T, DB> Encode<'q, DB> for RelayNodeID<T>
+where
+    T: RelayNode,
+    T::Key: Encode<'q, DB>,
+    DB: Database,
+{
+    fn encode_by_ref(
+        &self,
+        buf: &mut <DB as Database>::ArgumentBuffer<'q>,
+    ) -> Result<IsNull, BoxDynError> {
+        self.key().encode_by_ref(buf)
+    }
+
+    fn produces(&self) -> Option<DB::TypeInfo> {
+        self.key().produces()
+    }
+
+    fn size_hint(&self) -> usize {
+        self.key().size_hint()
+    }
+}
+
+impl<'r, T, DB> Decode<'r, DB> for RelayNodeID<T>
+where
+    T: RelayNode,
+    T::Key: Decode<'r, DB>,
+    DB: Database,
+{
+    fn decode(value: <DB as Database>::ValueRef<'r>) -> Result<Self, BoxDynError> {
+        T::Key::decode(value).map(RelayNodeID::new)
+    }
+}
@@ -0,0 +1,61 @@
+use std::{fmt::Debug, hash::Hash};
+
+use uuid::Uuid;
+
+use crate::RelayError;
+
+/// RelayNodeKey is implemented by every type which can be used as the primary key of a node.
+/// The key is converted into a string so it can be encoded into the relay ID by the 'RelayIdCodec'.
+/// Composite keys can be supported by implementing this trait on a struct containing each part of the key.
+pub trait RelayNodeKey: Clone + PartialEq + Eq + Hash + Debug + Send + Sync + 'static {
+    /// to_relay_key converts the key into the string which is encoded into the relay ID.
+    fn to_relay_key(&self) -> String;
+
+    /// from_relay_key converts the string decoded from a relay ID back into the key.
+    fn from_relay_key(key: &str) -> Result<Self, RelayError>;
+}
+
+fn invalid_key() -> RelayError {
+    RelayError::InvalidFormat("Invalid id provided to node query!".to_string())
+}
+
+impl RelayNodeKey for Uuid {
+    fn to_relay_key(&self) -> String {
+        self.as_simple().to_string()
+    }
+
+    fn from_relay_key(key: &str) -> Result<Self, RelayError> {
+        Uuid::parse_str(key).map_err(|_err| invalid_key())
+    }
+}
+
+impl RelayNodeKey for String {
+    fn to_relay_key(&self) -> String {
+        self.clone()
+    }
+
+    fn from_relay_key(key: &str) -> Result<Self, RelayError> {
+        Ok(key.to_string())
+    }
+}
+
+macro_rules! impl_relay_node_key_from_str {
+    ($($ty:ty),*) => {
+        $(
+            impl RelayNodeKey for $ty {
+                fn to_relay_key(&self) -> String {
+                    self.to_string()
+                }
+
+                fn from_relay_key(key: &str) -> Result<Self, RelayError> {
+                    key.parse().map_err(|_err| invalid_key())
+                }
+            }
+        )*
+    };
+}
+
+impl_relay_node_key_from_str!(i16, i32, i64, u16, u32, u64);
+
+#[cfg(feature = "ulid")]
+impl_relay_node_key_from_str!(ulid::Ulid);
@@ -0,0 +1,213 @@
+use std::{
+    cell::RefCell,
+    future::{poll_fn, Future},
+    pin::pin,
+    sync::Arc,
+};
+
+use async_graphql::{
+    extensions::{Extension, ExtensionContext, ExtensionFactory, NextExecute, NextSubscribe},
+    Response,
+};
+use futures_util::stream::BoxStream;
+
+use crate::RelayError;
+
+/// RelayKeyRing holds the secret keys used to protect relay ID's.
+/// The first key is used when creating new ID's while every key is accepted when reading an ID, which allows keys to be rotated without invalidating the ID's already held by clients.
+#[derive(Clone)]
+pub struct RelayKeyRing {
+    keys: Vec<Vec<u8>>,
+}
+
+impl RelayKeyRing {
+    /// new creates a key ring with the key which will be used to create new ID's.
+    pub fn new(primary_key: impl Into<Vec<u8>>) -> Self {
+        Self {
+            keys: vec![primary_key.into()],
+        }
+    }
+
+    /// with_verification_key adds an old key which is still accepted when reading an ID but is never used to create one.
+    pub fn with_verification_key(mut self, key: impl Into<Vec<u8>>) -> Self {
+        self.keys.push(key.into());
+        self
+    }
+
+    /// primary_key returns the key used to create new ID's.
+    pub(crate) fn primary_key(&self) -> &[u8] {
+        &self.keys[0]
+    }
+
+    /// keys returns every key which is accepted when reading an ID, starting with the primary key.
+    pub(crate) fn keys(&self) -> impl Iterator<Item = &[u8]> {
+        self.keys.iter().map(Vec::as_slice)
+    }
+}
+
+thread_local! {
+    static CURRENT_KEYS: RefCell<Option<Arc<RelayKeys>>> = const { RefCell::new(None) };
+}
+
+/// RelayKeys is an async-graphql extension which provides the keys used by the 'SignedCodec' and 'EncryptedCodec' to the requests of a schema.
+/// The keys are only available while a request of the schema is executed so each schema can use its own keys. 'RelayKeys::scope' and 'RelayKeys::run' can be used to encode or decode relay ID's outside of a request.
+/// Encoding or decoding a relay ID with one of these codecs while no keys are available returns the 'MissingKeys' error.
+/// ```ignore
+/// let keys = RelayKeys::new().with_signing_keys(RelayKeyRing::new(env::var("RELAY_KEY")?).with_verification_key(env::var("OLD_RELAY_KEY")?));
+/// let schema = Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+///     .extension(keys)
+///     .finish();
+/// ```
+#[derive(Clone, Default)]
+pub struct RelayKeys {
+    #[cfg(feature = "signed")]
+    signing: Option<RelayKeyRing>,
+    #[cfg(feature = "encrypted")]
+    encryption: Option<RelayKeyRing>,
+}
+
+impl RelayKeys {
+    /// new creates an empty set of keys.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// with_signing_keys sets the keys used by the 'SignedCodec' to sign and verify relay ID's.
+    #[cfg(feature = "signed")]
+    pub fn with_signing_keys(mut self, keys: RelayKeyRing) -> Self {
+        self.signing = Some(keys);
+        self
+    }
+
+    /// with_encryption_keys sets the keys used by the 'EncryptedCodec' to encrypt and decrypt relay ID's.
+    /// Keys of any length are accepted as they are hashed into a 256 bit key, however they should be randomly generated.
+    #[cfg(feature = "encrypted")]
+    pub fn with_encryption_keys(mut self, keys: RelayKeyRing) -> Self {
+        self.encryption = Some(keys);
+        self
+    }
+
+    /// scope makes the keys available to the codecs while the function is called.
+    pub fn scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        scope_keys(&Arc::new(self.clone()), f)
+    }
+
+    /// run makes the keys available to the codecs while the future is polled.
+    pub async fn run<F: Future>(&self, fut: F) -> F::Output {
+        run_with_keys(Arc::new(self.clone()), fut).await
+    }
+}
+
+/// KeysGuard restores the previous keys when a scope ends, even if it panics.
+struct KeysGuard(Option<Arc<RelayKeys>>);
+
+impl Drop for KeysGuard {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        CURRENT_KEYS.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+fn scope_keys<R>(keys: &Arc<RelayKeys>, f: impl FnOnce() -> R) -> R {
+    let previous = CURRENT_KEYS.with(|current| current.borrow_mut().replace(keys.clone()));
+    let _guard = KeysGuard(previous);
+    f()
+}
+
+async fn run_with_keys<F: Future>(keys: Arc<RelayKeys>, fut: F) -> F::Output {
+    let mut fut = pin!(fut);
+    poll_fn(|cx| scope_keys(&keys, || fut.as_mut().poll(cx))).await
+}
+
+fn with_keys<R>(
+    select: impl FnOnce(&RelayKeys) -> Option<&RelayKeyRing>,
+    f: impl FnOnce(&RelayKeyRing) -> R,
+) -> Result<R, RelayError> {
+    CURRENT_KEYS.with(|current| {
+        current
+            .borrow()
+            .as_deref()
+            .and_then(select)
+            .map(f)
+            .ok_or(RelayError::MissingKeys)
+    })
+}
+
+/// with_signing_keys calls the function with the signing keys of the current scope.
+#[cfg(feature = "signed")]
+pub(crate) fn with_signing_keys<R>(f: impl FnOnce(&RelayKeyRing) -> R) -> Result<R, RelayError> {
+    with_keys(|keys| keys.signing.as_ref(), f)
+}
+
+/// with_encryption_keys calls the function with the encryption keys of the current scope.
+#[cfg(feature = "encrypted")]
+pub(crate) fn with_encryption_keys<R>(f: impl FnOnce(&RelayKeyRing) -> R) -> Result<R, RelayError> {
+    with_keys(|keys| keys.encryption.as_ref(), f)
+}
+
+impl ExtensionFactory for RelayKeys {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(RelayKeysExtension(Arc::new(self.clone())))
+    }
+}
+
+struct RelayKeysExtension(Arc<RelayKeys>);
+
+#[async_trait::async_trait]
+impl Extension for RelayKeysExtension {
+    async fn execute(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        operation_name: Option<&str>,
+        next: NextExecute<'_>,
+    ) -> Response {
+        run_with_keys(self.0.clone(), next.run(ctx, operation_name)).await
+    }
+
+    fn subscribe<'s>(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        stream: BoxStream<'s, Response>,
+        next: NextSubscribe<'_>,
+    ) -> BoxStream<'s, Response> {
+        let keys = self.0.clone();
+        let mut stream = next.run(ctx, stream);
+        Box::pin(futures_util::stream::poll_fn(move |cx| {
+            scope_keys(&keys, || stream.as_mut().poll_next(cx))
+        }))
+    }
+}
+
+#[cfg(all(test, feature = "signed"))]
+mod tests {
+    use super::*;
+
+    fn primary_key() -> Result<Vec<u8>, RelayError> {
+        with_signing_keys(|keys| keys.primary_key().to_vec())
+    }
+
+    #[test]
+    fn scopes_are_nested() {
+        let outer = RelayKeys::new().with_signing_keys(RelayKeyRing::new("outer"));
+        let inner = RelayKeys::new().with_signing_keys(RelayKeyRing::new("inner"));
+        outer.scope(|| {
+            assert_eq!(primary_key(), Ok(b"outer".to_vec()));
+            inner.scope(|| assert_eq!(primary_key(), Ok(b"inner".to_vec())));
+            assert_eq!(primary_key(), Ok(b"outer".to_vec()));
+        });
+        assert_eq!(primary_key(), Err(RelayError::MissingKeys));
+    }
+
+    #[tokio::test]
+    async fn keys_are_available_while_the_future_runs() {
+        let keys = RelayKeys::new().with_signing_keys(RelayKeyRing::new("secret"));
+        let key = keys
+            .run(async {
+                tokio::task::yield_now().await;
+                primary_key()
+            })
+            .await;
+        assert_eq!(key, Ok(b"secret".to_vec()));
+        assert_eq!(primary_key(), Err(RelayError::MissingKeys));
+    }
+}
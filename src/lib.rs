@@ -4,22 +4,102 @@
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 
-use std::{any::Any, fmt, marker::PhantomData, str::FromStr};
+use std::{
+    any::{Any, TypeId},
+    borrow::Cow,
+    collections::HashMap,
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    str::FromStr,
+    sync::Arc,
+};
 
-use async_graphql::{Error, InputValueError, InputValueResult, Scalar, ScalarType, Value};
+use async_graphql::{
+    parser::types::Field,
+    registry::{MetaType, MetaTypeId, Registry},
+    Context, ContextSelectionSet, Error, ErrorExtensions, InputType, InputValueError,
+    InputValueResult, OutputType, Positioned, ScalarType, ServerResult, Value,
+};
 
 pub use async_graphql_relay_derive::*;
+pub use uuid;
 use uuid::Uuid;
 
+mod cache;
+mod codec;
+#[cfg(feature = "encrypted")]
+mod encrypted;
+mod error;
+mod integrations;
+mod key;
+#[cfg(any(feature = "signed", feature = "encrypted"))]
+mod keyring;
+#[cfg(feature = "dataloader")]
+mod loader;
+mod query;
+mod selection;
+#[cfg(feature = "serde")]
+pub mod serde_key;
+#[cfg(feature = "signed")]
+mod signed;
+mod stats;
+mod trace;
+
+pub use cache::*;
+pub use codec::*;
+#[cfg(feature = "encrypted")]
+pub use encrypted::*;
+pub use error::*;
+pub use key::*;
+#[cfg(any(feature = "signed", feature = "encrypted"))]
+pub use keyring::*;
+#[cfg(feature = "dataloader")]
+pub use loader::RelayNodeLoader;
+pub use query::*;
+pub use selection::*;
+#[cfg(feature = "signed")]
+pub use signed::*;
+pub use stats::RelayStats;
+#[cfg(feature = "tracing")]
+pub use trace::RelayTracing;
+
 /// RelayNodeInterface is a trait implemented by the GraphQL interface enum to implement the fetch_node method.
 /// You should refer to the 'RelayInterface' macro which is the recommended way to implement this trait.
 pub trait RelayNodeInterface
 where
     Self: Sized,
 {
+    /// Codec is used to encode and decode the relay ID's of the objects in this interface.
+    type Codec: RelayIdCodec;
+
+    /// MAX_NODES is the maximum number of relay ID's which can be passed to fetch_nodes. This can be changed using `#[relay(max_nodes = 50)]` on the 'RelayInterface' macro.
+    const MAX_NODES: usize = 100;
+
     /// fetch_node takes in a RelayContext and a generic relay ID and will return a Node interface with the requested object.
     /// This function is used to implement the 'node' query required by the Relay server specification for easily refetching an entity in the GraphQL schema.
-    fn fetch_node(ctx: RelayContext, relay_id: String) -> impl std::future::Future<Output = Result<Self, Error>> + Send;
+    /// A node which can't be found is returned as 'None' while an invalid ID or an ID of an unknown type is returned as an error.
+    fn fetch_node(ctx: RelayContext, relay_id: String) -> impl std::future::Future<Output = Result<Option<Self>, Error>> + Send;
+
+    /// fetch_node_ctx is the same as fetch_node but takes in the async-graphql Context of the resolver so the nodes can access the schema and request data using 'RelayNode::get_ctx'.
+    fn fetch_node_ctx(
+        ctx: &Context<'_>,
+        relay_id: String,
+    ) -> impl std::future::Future<Output = Result<Option<Self>, Error>> + Send;
+
+    /// fetch_nodes takes in a RelayContext and many generic relay ID's and will return the requested objects in the same order as the ID's.
+    /// The ID's are grouped by type and each type is fetched concurrently. A node which can't be found is returned as 'None' and an invalid ID or an ID of an unknown type as an error without failing the other ID's.
+    /// This function is used to implement the 'nodes' query recommended by the Relay server specification.
+    fn fetch_nodes(
+        ctx: RelayContext,
+        relay_ids: Vec<String>,
+    ) -> impl std::future::Future<Output = Result<Vec<Result<Option<Self>, Error>>, Error>> + Send;
+
+    /// fetch_nodes_ctx is the same as fetch_nodes but takes in the async-graphql Context of the resolver so the nodes can access the schema and request data using 'RelayNode::get_many_ctx'.
+    fn fetch_nodes_ctx(
+        ctx: &Context<'_>,
+        relay_ids: Vec<String>,
+    ) -> impl std::future::Future<Output = Result<Vec<Result<Option<Self>, Error>>, Error>> + Send;
 }
 
 /// RelayNodeStruct is a trait implemented by the GraphQL Object to ensure each Object has a globally unique ID.
@@ -29,40 +109,137 @@ pub trait RelayNodeStruct {
     /// ID_SUFFIX is the suffix appended to the nodes ID to create the relay ID.
     /// This MUST be unique for each type in the system.
     const ID_SUFFIX: &'static str;
+
+    /// Key is the type of the nodes primary key. This is a UUID by default but can be changed using `#[relay(key = "i64")]` on the 'RelayNodeObject' macro.
+    /// Keys other than a UUID are not a fixed length so the 'RelayNodeObject' macro uses the 'Base64Codec', which doesn't rely on the length of the key, when a key is provided.
+    type Key: RelayNodeKey;
+
+    /// Codec is used to encode and decode the relay ID's of this object. This is the 'SuffixCodec' by default, or the 'Base64Codec' when a custom key is used, but can be changed using `#[relay(id_format = "base64")]` or `#[relay(codec = "MyCodec")]` on the 'RelayNodeObject' macro.
+    /// Every object in an interface enum MUST use the same codec as the interface.
+    type Codec: RelayIdCodec;
+
+    /// LEGACY_ID_INPUT makes the scalar also accept the nodes bare key (e.g. a UUID) when the input isn't a relay ID, to help migrate existing clients. This can be enabled using `#[relay(legacy_id_input)]` on the 'RelayNodeObject' macro.
+    /// Relay ID's which belong to another type or have an invalid signature are still rejected and the bare key is never accepted when the codec is 'TAMPER_PROOF'.
+    const LEGACY_ID_INPUT: bool = false;
 }
 
 /// RelayNode is a trait implemented on the GraphQL Object to define how it should be fetched.
 /// This is used by the 'node' query so that the object can be refetched.
-pub trait RelayNode: RelayNodeStruct {
-    /// TNode is the type of the Node interface. This should point the enum with the 'RelayInterface' macro.
-    type TNode: RelayNodeInterface;
-
+/// The object is converted into the interface enum which dispatched the fetch by the 'RelayInterface' macro so the same object can be part of many interfaces.
+pub trait RelayNode: RelayNodeStruct + Send + Sync + Sized {
     /// get is a method defines by the user to refetch an object of a particular type.
     /// The context can be used to share a database connection or other required context to facilitate the refetch.
-    fn get(ctx: RelayContext, id: RelayNodeID<Self>) -> impl std::future::Future<Output = Result<Option<Self::TNode>, Error>> + Send;
+    /// This method must be implemented, so a node which can only be fetched using the async-graphql Context should implement 'get_ctx' and return an error from this method.
+    fn get(
+        ctx: RelayContext,
+        id: RelayNodeID<Self>,
+    ) -> impl std::future::Future<Output = Result<Option<Self>, Error>> + Send;
+
+    /// get_ctx is the same as get but takes in the async-graphql Context, giving access to the schema and request data, DataLoaders and the look ahead of the query.
+    /// It is used by 'fetch_node_ctx'. The default implementation calls 'get' with the RelayContext stored in the schema or request data and the 'RelayNodeSelection' of the resolver.
+    /// 'fetch_nodes_ctx' calls this method for each ID unless 'get_many_ctx' is implemented.
+    fn get_ctx(
+        ctx: &Context<'_>,
+        id: RelayNodeID<Self>,
+    ) -> impl std::future::Future<Output = Result<Option<Self>, Error>> + Send {
+        let mut relay_ctx = RelayContext::from_ctx(ctx);
+        relay_ctx.insert(RelayNodeSelection::from_ctx(ctx));
+        Self::get(relay_ctx, id)
+    }
+
+    /// authorize is called by 'fetch_node' and 'fetch_nodes' before the object is refetched to check if the ID can be accessed.
+    /// When false is returned the node isn't fetched and an error is returned in its place. The default implementation allows every ID.
+    fn authorize(
+        ctx: RelayContext,
+        id: &RelayNodeID<Self>,
+    ) -> impl std::future::Future<Output = Result<bool, Error>> + Send {
+        let _ = (ctx, id);
+        async move { Ok(true) }
+    }
+
+    /// authorize_ctx is the same as authorize but takes in the async-graphql Context and is used by 'fetch_node_ctx' and 'fetch_nodes_ctx'.
+    /// The default implementation calls 'authorize' with the RelayContext stored in the schema or request data.
+    fn authorize_ctx(
+        ctx: &Context<'_>,
+        id: &RelayNodeID<Self>,
+    ) -> impl std::future::Future<Output = Result<bool, Error>> + Send {
+        Self::authorize(RelayContext::from_ctx(ctx), id)
+    }
+
+    /// get_many refetches many objects of a particular type at once. It is used by 'fetch_nodes' and the 'RelayNodeLoader' so a type can be fetched using a single database query.
+    /// The results MUST be returned in the same order as the ID's and every ID fails with a 'Backend' error when the number of results doesn't match. The default implementation calls 'get' concurrently for each ID.
+    fn get_many(
+        ctx: RelayContext,
+        ids: Vec<RelayNodeID<Self>>,
+    ) -> impl std::future::Future<Output = Result<Vec<Option<Self>>, Error>> + Send {
+        async move {
+            futures_util::future::try_join_all(ids.into_iter().map(|id| Self::get(ctx.clone(), id)))
+                .await
+        }
+    }
+
+    /// get_many_ctx is the same as get_many but takes in the async-graphql Context and is used by 'fetch_nodes_ctx'.
+    /// The results MUST be returned in the same order as the ID's. The default implementation calls 'get_ctx' concurrently for each ID, so a node which fetches many objects at once in 'get_many' should call it from this method or use the 'RelayNodeLoader'.
+    fn get_many_ctx(
+        ctx: &Context<'_>,
+        ids: Vec<RelayNodeID<Self>>,
+    ) -> impl std::future::Future<Output = Result<Vec<Option<Self>>, Error>> + Send {
+        futures_util::future::try_join_all(ids.into_iter().map(|id| Self::get_ctx(ctx, id)))
+    }
 }
 
-/// RelayNodeID is a wrapper around a UUID with the use of the 'RelayNodeStruct' trait to ensure each object has a globally unique ID.
-#[derive(Clone, PartialEq, Eq)]
-pub struct RelayNodeID<T: RelayNode + ?Sized>(Uuid, PhantomData<T>);
+/// RelayNodeID is a wrapper around the nodes key with the use of the 'RelayNodeStruct' trait to ensure each object has a globally unique ID.
+#[cfg_attr(
+    feature = "diesel",
+    derive(diesel::expression::AsExpression, diesel::deserialize::FromSqlRow),
+    diesel(sql_type = diesel::sql_types::Uuid),
+    diesel(sql_type = diesel::sql_types::BigInt),
+    diesel(sql_type = diesel::sql_types::Integer),
+    diesel(sql_type = diesel::sql_types::SmallInt),
+    diesel(sql_type = diesel::sql_types::Text)
+)]
+pub struct RelayNodeID<T: RelayNode>(T::Key, PhantomData<T>);
 
 impl<T: RelayNode> RelayNodeID<T> {
-    /// new creates a new RelayNodeID from a UUID string and a type specified as a generic.
-    pub fn new(uuid: Uuid) -> Self {
-        RelayNodeID(uuid, PhantomData)
+    /// new creates a new RelayNodeID from the nodes key and a type specified as a generic.
+    pub fn new(key: T::Key) -> Self {
+        RelayNodeID(key, PhantomData)
     }
 
     /// new_from_relay_id takes in a generic relay ID and converts it into a RelayNodeID.
-    pub fn new_from_relay_id(relay_id: String) -> Result<Self, Error> {
-        if relay_id.len() < 32 {
-            return Err(Error::new("Invalid id provided to node query!"));
+    /// An error is returned if the relay ID belongs to a different type so it is safe to use with ID's provided by clients.
+    pub fn new_from_relay_id(relay_id: String) -> Result<Self, RelayError> {
+        let (suffix, key) = <T::Codec as RelayIdCodec>::decode(&relay_id)?;
+        if suffix != T::ID_SUFFIX {
+            return Err(RelayError::TypeMismatch {
+                expected: T::ID_SUFFIX,
+                actual: suffix,
+            });
         }
-        let (id, _) = relay_id.split_at(32);
-        let uuid = Uuid::parse_str(&id)
-            .map_err(|_err| Error::new("Invalid id provided to node query!"))?;
-        Ok(RelayNodeID(uuid, PhantomData))
+        let key = T::Key::from_relay_key(&key)?;
+        Ok(RelayNodeID(key, PhantomData))
     }
 
+    /// key returns a reference to the nodes key for use in DB queries or internal processing.
+    /// The key from this function is NOT globally unique!
+    pub fn key(&self) -> &T::Key {
+        &self.0
+    }
+
+    /// into_key converts the RelayNodeID into the nodes key.
+    /// The key from this function is NOT globally unique!
+    pub fn into_key(self) -> T::Key {
+        self.0
+    }
+
+    /// to_relay_id converts the RelayNodeID into the generic relay ID exposed to clients.
+    /// An error is returned if the codec can't encode the ID, e.g. because the 'RelayKeys' of the 'SignedCodec' aren't available.
+    pub fn to_relay_id(&self) -> Result<String, RelayError> {
+        <T::Codec as RelayIdCodec>::encode(T::ID_SUFFIX, &self.0.to_relay_key())
+    }
+}
+
+impl<T: RelayNode<Key = Uuid>> RelayNodeID<T> {
     /// new_from_str is a wrapper around 'Uuid::from_str' to create a new RelayNodeID from a UUIDv4 string.
     pub fn new_from_str(uuid: &str) -> Result<Self, uuid::Error> {
         Ok(Self::new(Uuid::from_str(uuid)?))
@@ -75,15 +252,38 @@ impl<T: RelayNode> RelayNodeID<T> {
     }
 }
 
+impl<T: RelayNode> Clone for RelayNodeID<T> {
+    fn clone(&self) -> Self {
+        RelayNodeID(self.0.clone(), PhantomData)
+    }
+}
+
+impl<T: RelayNode> PartialEq for RelayNodeID<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T: RelayNode> Eq for RelayNodeID<T> {}
+
+impl<T: RelayNode> Hash for RelayNodeID<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+/// The conversion panics if the codec can't encode the ID. Use 'RelayNodeID::to_relay_id' to handle the error.
 impl<T: RelayNode> From<&RelayNodeID<T>> for String {
     fn from(id: &RelayNodeID<T>) -> Self {
-        format!("{}{}", id.0.as_simple().to_string(), T::ID_SUFFIX)
+        id.to_relay_id()
+            .unwrap_or_else(|err| panic!("Failed to encode the relay ID: {}", err))
     }
 }
 
-impl<T: RelayNode> ToString for RelayNodeID<T> {
-    fn to_string(&self) -> String {
-        String::from(self)
+/// Formatting fails, which makes 'to_string' panic, if the codec can't encode the ID. Use 'RelayNodeID::to_relay_id' to handle the error.
+impl<T: RelayNode> fmt::Display for RelayNodeID<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_relay_id().map_err(|_err| fmt::Error)?)
     }
 }
 
@@ -93,42 +293,144 @@ impl<T: RelayNode> fmt::Debug for RelayNodeID<T> {
     }
 }
 
-#[Scalar]
-impl<T: RelayNode + Send + Sync> ScalarType for RelayNodeID<T> {
+const SCALAR_DESCRIPTION: &str = "The scalar accepts the same relay ID it outputs and rejects relay ID's belonging to other types.\nObjects using `#[relay(legacy_id_input)]` also accept the nodes bare key (e.g. a UUID) to help migrate existing clients.";
+
+impl<T: RelayNode> ScalarType for RelayNodeID<T> {
     fn parse(value: Value) -> InputValueResult<Self> {
         match value {
-            Value::String(s) => Ok(RelayNodeID::<T>::new_from_str(&s)?),
+            Value::String(s) => match RelayNodeID::<T>::new_from_relay_id(s.clone()) {
+                Ok(id) => Ok(id),
+                Err(err @ RelayError::InvalidFormat(_))
+                    if T::LEGACY_ID_INPUT && !<T::Codec as RelayIdCodec>::TAMPER_PROOF =>
+                {
+                    match T::Key::from_relay_key(&s) {
+                        Ok(key) => Ok(RelayNodeID::new(key)),
+                        Err(_) => Err(err.into_input_value_error()),
+                    }
+                }
+                Err(err) => Err(err.into_input_value_error()),
+            },
             _ => Err(InputValueError::expected_type(value)),
         }
     }
 
+    /// to_value returns null if the codec can't encode the ID. The ID's returned by a resolver return the error instead.
+    fn to_value(&self) -> Value {
+        self.to_relay_id().map(Value::String).unwrap_or(Value::Null)
+    }
+}
+
+fn scalar_type_info() -> MetaType {
+    MetaType::Scalar {
+        name: "RelayNodeID".to_string(),
+        description: Some(SCALAR_DESCRIPTION.to_string()),
+        is_valid: None,
+        visible: None,
+        inaccessible: false,
+        tags: Vec::new(),
+        specified_by_url: None,
+        directive_invocations: Vec::new(),
+        requires_scopes: Vec::new(),
+    }
+}
+
+// The scalar is implemented by hand instead of using '#[Scalar]' so an ID which can't be encoded is returned as a field error.
+impl<T: RelayNode> InputType for RelayNodeID<T> {
+    type RawValueType = Self;
+
+    fn type_name() -> Cow<'static, str> {
+        Cow::Borrowed("RelayNodeID")
+    }
+
+    fn create_type_info(registry: &mut Registry) -> String {
+        registry.create_input_type::<Self, _>(MetaTypeId::Scalar, |_| scalar_type_info())
+    }
+
+    fn parse(value: Option<Value>) -> InputValueResult<Self> {
+        <Self as ScalarType>::parse(value.unwrap_or_default())
+    }
+
     fn to_value(&self) -> Value {
-        Value::String(String::from(self))
+        <Self as ScalarType>::to_value(self)
+    }
+
+    fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+        Some(self)
+    }
+}
+
+impl<T: RelayNode> OutputType for RelayNodeID<T> {
+    fn type_name() -> Cow<'static, str> {
+        Cow::Borrowed("RelayNodeID")
+    }
+
+    fn create_type_info(registry: &mut Registry) -> String {
+        registry.create_output_type::<Self, _>(MetaTypeId::Scalar, |_| scalar_type_info())
+    }
+
+    async fn resolve(
+        &self,
+        ctx: &ContextSelectionSet<'_>,
+        field: &Positioned<Field>,
+    ) -> ServerResult<Value> {
+        self.to_relay_id()
+            .map(Value::String)
+            .map_err(|err| ctx.set_error_path(err.extend().into_server_error(field.pos)))
     }
 }
 
 /// RelayContext allows context to be parsed to the `get` handler to facilitate refetching of objects.
 /// This is designed for parsing the Database connection but could be used for any global state.
-pub struct RelayContext(Box<dyn Any + Sync + Send>);
+/// It holds at most one value of each type so the Database connection, the current user and any other data can be stored side by side.
+/// The context is cheap to clone so it can be shared between the objects fetched by 'fetch_nodes'.
+#[derive(Clone, Default)]
+pub struct RelayContext(Arc<HashMap<TypeId, Arc<dyn Any + Sync + Send>>>);
 
 impl RelayContext {
     /// Create a new context which stores a piece of data.
     pub fn new<T: Any + Sync + Send>(data: T) -> Self {
-        Self(Box::new(data))
+        let mut ctx = Self::nil();
+        ctx.insert(data);
+        ctx
+    }
+
+    /// Get the context stored in the schema or request data of an async-graphql Context, or an empty context if none was set.
+    /// The request data used by the 'RelayStats' extension is added to the context so the nodes fetched with it are counted.
+    pub fn from_ctx(ctx: &Context<'_>) -> Self {
+        let mut relay_ctx = ctx
+            .data_opt::<RelayContext>()
+            .cloned()
+            .unwrap_or_else(RelayContext::nil);
+        stats::RelayStatsRecorder::attach(&mut relay_ctx, ctx);
+        relay_ctx
     }
 
     /// Create a new empty context. This can be used if you have no data to put in the context.
     pub fn nil() -> Self {
-        let nil: Option<()> = None;
-        Self(Box::new(nil))
+        Self::default()
+    }
+
+    /// Insert a piece of data into the context, replacing any existing data of the same type.
+    pub fn insert<T: Any + Sync + Send>(&mut self, data: T) -> &mut Self {
+        Arc::make_mut(&mut self.0).insert(TypeId::of::<T>(), Arc::new(data));
+        self
     }
 
     /// Get a pointer to the data stored in the context if it can be found.
     pub fn get<T: Any + Sync + Send>(&self) -> Option<&T> {
-        match self.0.downcast_ref::<T>() {
-            Some(v) => Some(v),
-            _ => None,
-        }
+        self.0
+            .get(&TypeId::of::<T>())
+            .and_then(|data| data.downcast_ref::<T>())
+    }
+
+    /// Get a pointer to the data stored in the context or an error if it was never inserted.
+    pub fn get_required<T: Any + Sync + Send>(&self) -> Result<&T, Error> {
+        self.get::<T>().ok_or_else(|| {
+            Error::new(format!(
+                "Data of type '{}' was not found in the RelayContext!",
+                std::any::type_name::<T>()
+            ))
+        })
     }
 }
 
@@ -138,8 +440,214 @@ impl<T: RelayNode> serde::Serialize for RelayNodeID<T> {
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        let relay_id = self.to_relay_id().map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(&relay_id)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: RelayNode> serde::Deserialize<'de> for RelayNodeID<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let relay_id = String::deserialize(deserializer)?;
+        RelayNodeID::new_from_relay_id(relay_id).map_err(serde::de::Error::custom)
     }
 }
 
-// TODO: Unit tests
+#[doc(hidden)]
+pub mod __private {
+    use std::{future::Future, pin::Pin};
+
+    use async_graphql::{Context, Error, ErrorExtensions};
+    pub use futures_util::future::join_all;
+    #[cfg(feature = "serde")]
+    pub use serde;
+
+    #[cfg(feature = "dataloader")]
+    pub use crate::loader::{load_node, load_node_ctx, load_nodes_ctx};
+    pub use crate::stats::StatsHandle;
+    pub use crate::trace::{
+        fetch_node_span, fetch_nodes_span, relay_error, traced, NodeObserver, TraceSpan,
+    };
+
+    use crate::{
+        error::check_node_count,
+        stats::RelayStatsRecorder,
+        trace::{get_many_span, node_type},
+        RelayContext, RelayError, RelayNode, RelayNodeCache, RelayNodeID, RelayNodeSelection,
+        RelayNodeStruct,
+    };
+
+    /// assert_codec fails to compile unless the object uses the codec of the interface enum it is part of.
+    pub fn assert_codec<T: RelayNodeStruct<Codec = C>, C>() {}
+
+    /// NodeGroup is the result of fetching the ID's of a single type in 'fetch_nodes'. Each result is paired with the index of its ID.
+    pub type NodeGroup<'a, N> =
+        Pin<Box<dyn Future<Output = Vec<(usize, Result<Option<N>, Error>)>> + Send + 'a>>;
+
+    /// authorize_node checks 'RelayNode::authorize' for an ID and returns an error if access is denied.
+    pub async fn authorize_node<T: RelayNode>(
+        ctx: RelayContext,
+        id: &RelayNodeID<T>,
+    ) -> Result<(), Error> {
+        match T::authorize(ctx, id).await.map_err(backend_error)? {
+            true => Ok(()),
+            false => Err(unauthorized()),
+        }
+    }
+
+    /// authorize_node_ctx checks 'RelayNode::authorize_ctx' for an ID and returns an error if access is denied.
+    pub async fn authorize_node_ctx<T: RelayNode>(
+        ctx: &Context<'_>,
+        id: &RelayNodeID<T>,
+    ) -> Result<(), Error> {
+        match T::authorize_ctx(ctx, id).await.map_err(backend_error)? {
+            true => Ok(()),
+            false => Err(unauthorized()),
+        }
+    }
+
+    fn unauthorized() -> Error {
+        RelayError::Unauthorized.extend()
+    }
+
+    /// backend_error adds the 'BACKEND' code to an error returned while fetching a node unless the error already has extensions.
+    pub fn backend_error(err: Error) -> Error {
+        match err.extensions {
+            Some(_) => err,
+            None => Error {
+                source: err.source.clone(),
+                ..RelayError::Backend(err.message).extend()
+            },
+        }
+    }
+
+    /// cached_node returns the node from the 'RelayNodeCache' if there is one, otherwise it is fetched and stored in the cache.
+    pub async fn cached_node<N, F>(
+        cache: Option<RelayNodeCache>,
+        stats: StatsHandle,
+        relay_id: &str,
+        selection: Option<RelayNodeSelection>,
+        fetch: F,
+    ) -> Result<Option<N>, Error>
+    where
+        N: Clone + Send + Sync + 'static,
+        F: Future<Output = Result<Option<N>, Error>>,
+    {
+        match cache {
+            Some(cache) => {
+                let on_hit = || {
+                    if let Some(recorder) = stats.recorder() {
+                        recorder.record_cache_hit();
+                    }
+                };
+                cache.get_or_fetch(relay_id, selection, fetch, on_hit).await
+            }
+            None => fetch.await,
+        }
+    }
+
+    /// fetch_node_group fetches the ID's of a single type for 'fetch_nodes' using 'RelayNode::get_many' and converts the nodes into the interface enum.
+    /// Each ID is checked with 'RelayNode::authorize' first and only the authorized ID's are fetched.
+    pub fn fetch_node_group<'a, T, N>(
+        ctx: RelayContext,
+        ids: Vec<(usize, RelayNodeID<T>)>,
+    ) -> NodeGroup<'a, N>
+    where
+        T: RelayNode + Into<N> + 'a,
+        N: Send + 'a,
+    {
+        Box::pin(async move {
+            let checks = join_all(ids.iter().map(|(_, id)| authorize_node(ctx.clone(), id))).await;
+            let recorder = ctx.get::<RelayStatsRecorder>().cloned();
+            fetch_group(recorder, ids, checks, |authorized| {
+                T::get_many(ctx, authorized)
+            })
+            .await
+        })
+    }
+
+    /// fetch_node_group_ctx fetches the ID's of a single type for 'fetch_nodes_ctx' using 'RelayNode::get_many_ctx', or the 'RelayNodeLoader', and converts the nodes into the interface enum.
+    /// Each ID is checked with 'RelayNode::authorize_ctx' first and only the authorized ID's are fetched.
+    pub fn fetch_node_group_ctx<'a, T, N, G, F>(
+        ctx: &'a Context<'a>,
+        ids: Vec<(usize, RelayNodeID<T>)>,
+        get_many: G,
+    ) -> NodeGroup<'a, N>
+    where
+        T: RelayNode + Into<N> + 'a,
+        N: Send + 'a,
+        G: FnOnce(&'a Context<'a>, Vec<RelayNodeID<T>>) -> F + Send + 'a,
+        F: Future<Output = Result<Vec<Option<T>>, Error>> + Send + 'a,
+    {
+        Box::pin(async move {
+            let checks = join_all(ids.iter().map(|(_, id)| authorize_node_ctx(ctx, id))).await;
+            let recorder = StatsHandle::from_ctx(ctx).recorder().cloned();
+            fetch_group(recorder, ids, checks, |authorized| {
+                get_many(ctx, authorized)
+            })
+            .await
+        })
+    }
+
+    /// fetch_group fetches the ID's which passed their authorization check and records the 'RelayStats' of the group.
+    async fn fetch_group<T, N, F>(
+        recorder: Option<RelayStatsRecorder>,
+        ids: Vec<(usize, RelayNodeID<T>)>,
+        checks: Vec<Result<(), Error>>,
+        get_many: impl FnOnce(Vec<RelayNodeID<T>>) -> F,
+    ) -> Vec<(usize, Result<Option<N>, Error>)>
+    where
+        T: RelayNode + Into<N>,
+        F: Future<Output = Result<Vec<Option<T>>, Error>>,
+    {
+        if ids.is_empty() {
+            return Vec::new();
+        }
+
+        let count = ids.len() as u64;
+        let mut results = Vec::with_capacity(ids.len());
+        let (mut indexes, mut authorized) = (Vec::new(), Vec::new());
+        for ((index, id), check) in ids.into_iter().zip(checks) {
+            match check {
+                Ok(()) => {
+                    indexes.push(index);
+                    authorized.push(id);
+                }
+                Err(err) => results.push((index, Err(err))),
+            }
+        }
+        if authorized.is_empty() {
+            if let Some(recorder) = recorder {
+                recorder.record_fetches(node_type::<T>(), count, 0, count);
+            }
+            return results;
+        }
+
+        let span = get_many_span(&authorized);
+        let len = authorized.len();
+        match traced(span, get_many(authorized))
+            .await
+            .and_then(|nodes| check_node_count(len, nodes))
+            .map_err(backend_error)
+        {
+            Ok(nodes) => results.extend(
+                indexes
+                    .into_iter()
+                    .zip(nodes.into_iter().map(|node| Ok(node.map(Into::into)))),
+            ),
+            Err(err) => results.extend(indexes.into_iter().map(|index| (index, Err(err.clone())))),
+        }
+        if let Some(recorder) = recorder {
+            let not_found = results
+                .iter()
+                .filter(|(_, result)| matches!(result, Ok(None)))
+                .count() as u64;
+            let errors = results.iter().filter(|(_, result)| result.is_err()).count() as u64;
+            recorder.record_fetches(node_type::<T>(), count, not_found, errors);
+        }
+        results
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+
+use async_graphql::{
+    dataloader::{DataLoader, Loader},
+    Context, Error,
+};
+
+use crate::{error::check_node_count, RelayContext, RelayNode, RelayNodeID, RelayNodeSelection};
+
+/// RelayNodeLoader is an async-graphql DataLoader which batches the nodes fetched within a request into a single call to 'RelayNode::get_many' per type.
+/// The DataLoader should be stored in the RelayContext passed to 'fetch_node', or in the schema or request data when using 'fetch_node_ctx', and the interface enum must use `#[relay(dataloader)]` on the 'RelayInterface' macro.
+/// The RelayContext given to the loader is the one passed to 'get_many'. Nodes loaded by the relay queries are keyed by their 'RelayNodeSelection' so each selection is fetched using its own call to 'get_many' with the selection stored in the RelayContext.
+/// ```ignore
+/// let loader = DataLoader::new(RelayNodeLoader::new(RelayContext::new(pool)), tokio::spawn);
+/// Node::fetch_node(RelayContext::new(loader), id).await
+/// ```
+pub struct RelayNodeLoader(RelayContext);
+
+impl RelayNodeLoader {
+    /// new creates a loader which will pass the context to 'get_many'.
+    pub fn new(ctx: RelayContext) -> Self {
+        Self(ctx)
+    }
+}
+
+impl<T> Loader<RelayNodeID<T>> for RelayNodeLoader
+where
+    T: RelayNode + Clone + 'static,
+{
+    type Value = T;
+    type Error = Error;
+
+    async fn load(
+        &self,
+        keys: &[RelayNodeID<T>],
+    ) -> Result<HashMap<RelayNodeID<T>, Self::Value>, Self::Error> {
+        let nodes = check_node_count(
+            keys.len(),
+            T::get_many(self.0.clone(), keys.to_vec()).await?,
+        )?;
+        Ok(keys
+            .iter()
+            .cloned()
+            .zip(nodes)
+            .filter_map(|(id, node)| node.map(|node| (id, node)))
+            .collect())
+    }
+}
+
+impl<T> Loader<(RelayNodeID<T>, RelayNodeSelection)> for RelayNodeLoader
+where
+    T: RelayNode + Clone + 'static,
+{
+    type Value = T;
+    type Error = Error;
+
+    async fn load(
+        &self,
+        keys: &[(RelayNodeID<T>, RelayNodeSelection)],
+    ) -> Result<HashMap<(RelayNodeID<T>, RelayNodeSelection), Self::Value>, Self::Error> {
+        let mut groups = HashMap::<&RelayNodeSelection, Vec<RelayNodeID<T>>>::new();
+        for (id, selection) in keys {
+            groups.entry(selection).or_default().push(id.clone());
+        }
+
+        let groups =
+            futures_util::future::try_join_all(groups.into_iter().map(|(selection, ids)| {
+                let mut ctx = self.0.clone();
+                ctx.insert(selection.clone());
+                async move {
+                    let nodes = check_node_count(ids.len(), T::get_many(ctx, ids.clone()).await?)?;
+                    Ok::<_, Error>((selection, ids, nodes))
+                }
+            }))
+            .await?;
+        Ok(groups
+            .into_iter()
+            .flat_map(|(selection, ids, nodes)| {
+                ids.into_iter()
+                    .zip(nodes)
+                    .filter_map(move |(id, node)| node.map(|node| ((id, selection.clone()), node)))
+            })
+            .collect())
+    }
+}
+
+/// load_node_ctx fetches a node using the 'RelayNodeLoader' stored in the async-graphql Context, falling back to 'RelayNode::get_ctx' when there is no loader.
+pub async fn load_node_ctx<T>(ctx: &Context<'_>, id: RelayNodeID<T>) -> Result<Option<T>, Error>
+where
+    T: RelayNode + Clone + 'static,
+{
+    match ctx.data_opt::<DataLoader<RelayNodeLoader>>() {
+        Some(loader) => {
+            loader
+                .load_one((id, RelayNodeSelection::from_ctx(ctx)))
+                .await
+        }
+        None => T::get_ctx(ctx, id).await,
+    }
+}
+
+/// load_nodes_ctx fetches many nodes using the 'RelayNodeLoader' stored in the async-graphql Context, falling back to 'RelayNode::get_many_ctx' when there is no loader.
+pub async fn load_nodes_ctx<T>(
+    ctx: &Context<'_>,
+    ids: Vec<RelayNodeID<T>>,
+) -> Result<Vec<Option<T>>, Error>
+where
+    T: RelayNode + Clone + 'static,
+{
+    match ctx.data_opt::<DataLoader<RelayNodeLoader>>() {
+        Some(loader) => {
+            let selection = RelayNodeSelection::from_ctx(ctx);
+            let keys = ids
+                .into_iter()
+                .map(|id| (id, selection.clone()))
+                .collect::<Vec<_>>();
+            let nodes = loader.load_many(keys.iter().cloned()).await?;
+            Ok(keys.iter().map(|key| nodes.get(key).cloned()).collect())
+        }
+        None => T::get_many_ctx(ctx, ids).await,
+    }
+}
+
+/// load_node fetches a node using the 'RelayNodeLoader' stored in the context, falling back to 'RelayNode::get' when there is no loader.
+pub async fn load_node<T>(ctx: RelayContext, id: RelayNodeID<T>) -> Result<Option<T>, Error>
+where
+    T: RelayNode + Clone + 'static,
+{
+    match (
+        ctx.get::<DataLoader<RelayNodeLoader>>(),
+        ctx.get::<RelayNodeSelection>(),
+    ) {
+        (Some(loader), Some(selection)) => loader.load_one((id, selection.clone())).await,
+        (Some(loader), None) => loader.load_one(id).await,
+        (None, _) => T::get(ctx, id).await,
+    }
+}
@@ -0,0 +1,86 @@
+use std::marker::PhantomData;
+
+use async_graphql::{Context, Error, Object, OutputType, ID};
+
+use crate::{stats::RelayStatsRecorder, RelayContext, RelayNodeInterface, RelayNodeSelection};
+
+/// RelayNodeQuery is a GraphQL Object which implements the 'node' and 'nodes' queries for the Node interface `N`.
+/// It should be merged into the query root using async-graphql's 'MergedObject'.
+/// By default the nodes are fetched with the async-graphql Context using 'fetch_node_ctx' so the RelayContext should be stored in the schema data.
+/// Use 'RelayNodeQuery::with_context' to always use a fixed RelayContext instead. The 'RelayNodeSelection' of the query is added to it before fetching the nodes.
+/// ```ignore
+/// #[derive(MergedObject, Default)]
+/// pub struct QueryRoot(MyQuery, RelayNodeQuery<Node>);
+/// ```
+pub struct RelayNodeQuery<N> {
+    ctx: Option<RelayContext>,
+    phantom: PhantomData<fn() -> N>,
+}
+
+impl<N> RelayNodeQuery<N> {
+    /// new creates a query which fetches the nodes using the async-graphql Context of the request.
+    pub fn new() -> Self {
+        Self {
+            ctx: None,
+            phantom: PhantomData,
+        }
+    }
+
+    /// with_context creates a query which passes the provided RelayContext to 'fetch_node' and 'fetch_nodes'.
+    pub fn with_context(ctx: RelayContext) -> Self {
+        Self {
+            ctx: Some(ctx),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<N> Default for RelayNodeQuery<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[Object]
+impl<N> RelayNodeQuery<N>
+where
+    N: RelayNodeInterface + OutputType,
+{
+    /// Fetches an object given its ID.
+    async fn node(&self, ctx: &Context<'_>, id: ID) -> Result<Option<N>, Error> {
+        match &self.ctx {
+            Some(relay_ctx) => N::fetch_node(request_ctx(relay_ctx, ctx), id.0).await,
+            None => N::fetch_node_ctx(ctx, id.0).await,
+        }
+    }
+
+    /// Lookup nodes by a list of IDs.
+    async fn nodes(&self, ctx: &Context<'_>, ids: Vec<ID>) -> Result<Vec<Option<N>>, Error> {
+        let ids = ids.into_iter().map(|id| id.0).collect();
+        let nodes = match &self.ctx {
+            Some(relay_ctx) => N::fetch_nodes(request_ctx(relay_ctx, ctx), ids).await?,
+            None => N::fetch_nodes_ctx(ctx, ids).await?,
+        };
+
+        Ok(nodes
+            .into_iter()
+            .enumerate()
+            .map(|(index, node)| {
+                node.unwrap_or_else(|err| {
+                    let mut err = ctx.set_error_path(err.into_server_error(ctx.item.pos));
+                    err.path.push(async_graphql::PathSegment::Index(index));
+                    ctx.add_error(err);
+                    None
+                })
+            })
+            .collect())
+    }
+}
+
+/// request_ctx adds the stats recorder and the 'RelayNodeSelection' of the query to the RelayContext.
+fn request_ctx(relay_ctx: &RelayContext, ctx: &Context<'_>) -> RelayContext {
+    let mut relay_ctx = relay_ctx.clone();
+    RelayStatsRecorder::attach(&mut relay_ctx, ctx);
+    relay_ctx.insert(RelayNodeSelection::from_ctx(ctx));
+    relay_ctx
+}
@@ -0,0 +1,68 @@
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+};
+
+use async_graphql::{Context, SelectionField};
+
+/// RelayNodeSelection is the set of fields selected on a node by the query, including the fields selected using fragments.
+/// It is stored in the RelayContext passed to 'RelayNode::get' by 'fetch_node_ctx', 'fetch_nodes_ctx' and the 'RelayNodeQuery' so the node can be fetched using a minimal database query.
+/// Fragments are merged regardless of their type condition so the selection can include fields belonging to other types in the interface.
+/// The 'RelayNodeCache' and 'RelayNodeLoader' only share nodes between fields with the same selection.
+/// ```ignore
+/// let selection = ctx.get::<RelayNodeSelection>();
+/// if selection.map_or(true, |selection| selection.contains("posts")) {
+///     // Join the users posts
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RelayNodeSelection(HashMap<String, RelayNodeSelection>);
+
+impl RelayNodeSelection {
+    /// from_ctx creates the selection of the field currently being resolved. For the 'nodes' query this is the selection of each node.
+    pub fn from_ctx(ctx: &Context<'_>) -> Self {
+        Self::from_field(ctx.field())
+    }
+
+    fn from_field(field: SelectionField<'_>) -> Self {
+        let mut selection = Self::default();
+        for field in field.selection_set() {
+            selection
+                .0
+                .entry(field.name().to_string())
+                .or_default()
+                .merge(Self::from_field(field));
+        }
+        selection
+    }
+
+    /// merge adds the fields of another selection, merging the selections of fields which were selected by both.
+    fn merge(&mut self, other: Self) {
+        for (name, nested) in other.0 {
+            self.0.entry(name).or_default().merge(nested);
+        }
+    }
+
+    /// contains returns true if the field was selected.
+    pub fn contains(&self, name: &str) -> bool {
+        self.0.contains_key(name)
+    }
+
+    /// field returns the selection of a field so the selection of nested objects can be checked.
+    pub fn field(&self, name: &str) -> Option<&RelayNodeSelection> {
+        self.0.get(name)
+    }
+
+    /// fields returns the name of every selected field.
+    pub fn fields(&self) -> impl Iterator<Item = &str> {
+        self.0.keys().map(String::as_str)
+    }
+}
+
+impl Hash for RelayNodeSelection {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut fields = self.0.iter().collect::<Vec<_>>();
+        fields.sort_unstable_by_key(|(name, _)| *name);
+        fields.hash(state);
+    }
+}
@@ -0,0 +1,33 @@
+//! serde_key (de)serializes a RelayNodeID as the nodes bare key instead of the relay ID.
+//! This is useful when storing ID's internally, e.g. in a database or job queue, as it doesn't depend on the codec or its keys.
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! pub struct SendWelcomeEmail {
+//!     #[serde(with = "async_graphql_relay::serde_key")]
+//!     pub user_id: RelayNodeID<User>,
+//! }
+//! ```
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{RelayNode, RelayNodeID};
+
+/// serialize serializes the RelayNodeID as the nodes key.
+pub fn serialize<T, S>(id: &RelayNodeID<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: RelayNode,
+    T::Key: Serialize,
+    S: Serializer,
+{
+    id.key().serialize(serializer)
+}
+
+/// deserialize deserializes the nodes key into a RelayNodeID.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<RelayNodeID<T>, D::Error>
+where
+    T: RelayNode,
+    T::Key: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::Key::deserialize(deserializer).map(RelayNodeID::new)
+}
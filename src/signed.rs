@@ -0,0 +1,151 @@
+use std::marker::PhantomData;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::{keyring::with_signing_keys, RelayError, RelayIdCodec, SuffixCodec};
+
+/// SIGNATURE_LENGTH is the number of bytes of the HMAC-SHA256 tag which are appended to the relay ID.
+const SIGNATURE_LENGTH: usize = 16;
+
+/// SignedCodec wraps another codec and appends a HMAC-SHA256 tag to the relay ID's it creates, e.g. '92ba0c2d4b4e4e2991dd8f96a078c3ffu.XJ4fcPmT1wV0B6Fc2yJd7A'.
+/// ID's which have been modified by a client or were created for another type are rejected when they are decoded, so ID's can't be forged or enumerated.
+/// The keys are provided by the 'RelayKeys' extension of the schema using 'RelayKeys::with_signing_keys'.
+pub struct SignedCodec<C: RelayIdCodec = SuffixCodec>(PhantomData<C>);
+
+fn mac(key: &[u8], relay_id: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC can take a key of any size");
+    mac.update(relay_id.as_bytes());
+    mac
+}
+
+impl<C: RelayIdCodec> RelayIdCodec for SignedCodec<C> {
+    const TAMPER_PROOF: bool = true;
+
+    fn encode(type_tag: &str, key: &str) -> Result<String, RelayError> {
+        let relay_id = C::encode(type_tag, key)?;
+        let signature =
+            with_signing_keys(|keys| mac(keys.primary_key(), &relay_id).finalize().into_bytes())?;
+        Ok(format!(
+            "{}.{}",
+            relay_id,
+            URL_SAFE_NO_PAD.encode(&signature[..SIGNATURE_LENGTH])
+        ))
+    }
+
+    fn decode(relay_id: &str) -> Result<(String, String), RelayError> {
+        let (relay_id, signature) = relay_id
+            .rsplit_once('.')
+            .ok_or(RelayError::InvalidSignature)?;
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature)
+            .map_err(|_err| RelayError::InvalidSignature)?;
+        if signature.len() != SIGNATURE_LENGTH {
+            return Err(RelayError::InvalidSignature);
+        }
+
+        let verified = with_signing_keys(|keys| {
+            keys.keys()
+                .any(|key| mac(key, relay_id).verify_truncated_left(&signature).is_ok())
+        })?;
+        if !verified {
+            return Err(RelayError::InvalidSignature);
+        }
+
+        C::decode(relay_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RelayKeyRing, RelayKeys};
+
+    const KEY: &str = "92ba0c2d4b4e4e2991dd8f96a078c3ff";
+
+    fn keys(keys: RelayKeyRing) -> RelayKeys {
+        RelayKeys::new().with_signing_keys(keys)
+    }
+
+    fn encode(type_tag: &str) -> String {
+        SignedCodec::<SuffixCodec>::encode(type_tag, KEY).unwrap()
+    }
+
+    fn decode(relay_id: &str) -> Result<(String, String), RelayError> {
+        SignedCodec::<SuffixCodec>::decode(relay_id)
+    }
+
+    #[test]
+    fn round_trip() {
+        keys(RelayKeyRing::new("secret")).scope(|| {
+            let relay_id = encode("u");
+            assert!(relay_id.starts_with("92ba0c2d4b4e4e2991dd8f96a078c3ffu."));
+            assert_eq!(decode(&relay_id), Ok(("u".to_string(), KEY.to_string())));
+        });
+    }
+
+    #[test]
+    fn tampered_payload() {
+        keys(RelayKeyRing::new("secret")).scope(|| {
+            let relay_id = encode("u").replacen("92ba", "92bb", 1);
+            assert_eq!(decode(&relay_id), Err(RelayError::InvalidSignature));
+        });
+    }
+
+    #[test]
+    fn tampered_tag() {
+        keys(RelayKeyRing::new("secret")).scope(|| {
+            let relay_id = encode("u");
+            let (payload, signature) = relay_id.rsplit_once('.').unwrap();
+            let mut signature = URL_SAFE_NO_PAD.decode(signature).unwrap();
+            signature[0] ^= 1;
+            let relay_id = format!("{}.{}", payload, URL_SAFE_NO_PAD.encode(signature));
+            assert_eq!(decode(&relay_id), Err(RelayError::InvalidSignature));
+            assert_eq!(decode(payload), Err(RelayError::InvalidSignature));
+            assert_eq!(
+                decode(&format!("{}.AAAA", payload)),
+                Err(RelayError::InvalidSignature)
+            );
+        });
+    }
+
+    #[test]
+    fn tag_of_another_type() {
+        keys(RelayKeyRing::new("secret")).scope(|| {
+            let tenant_id = encode("t");
+            let (_, signature) = tenant_id.rsplit_once('.').unwrap();
+            let relay_id = format!("{}u.{}", KEY, signature);
+            assert_eq!(decode(&relay_id), Err(RelayError::InvalidSignature));
+        });
+    }
+
+    #[test]
+    fn key_rotation() {
+        let old_id = keys(RelayKeyRing::new("old")).scope(|| encode("u"));
+        let rotated = keys(RelayKeyRing::new("new").with_verification_key("old"));
+        let new_id = rotated.scope(|| {
+            assert_eq!(decode(&old_id), Ok(("u".to_string(), KEY.to_string())));
+            encode("u")
+        });
+
+        assert_ne!(old_id, new_id);
+        keys(RelayKeyRing::new("new")).scope(|| {
+            assert_eq!(encode("u"), new_id);
+            assert_eq!(decode(&old_id), Err(RelayError::InvalidSignature));
+        });
+        keys(RelayKeyRing::new("old")).scope(|| {
+            assert_eq!(decode(&new_id), Err(RelayError::InvalidSignature));
+        });
+    }
+
+    #[test]
+    fn missing_keys() {
+        let relay_id = keys(RelayKeyRing::new("secret")).scope(|| encode("u"));
+        assert_eq!(
+            SignedCodec::<SuffixCodec>::encode("u", KEY),
+            Err(RelayError::MissingKeys)
+        );
+        assert_eq!(decode(&relay_id), Err(RelayError::MissingKeys));
+    }
+}
@@ -0,0 +1,145 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+use async_graphql::{
+    extensions::{Extension, ExtensionContext, ExtensionFactory, NextPrepareRequest, NextRequest},
+    indexmap::IndexMap,
+    Context, Name, Request, Response, ServerResult, Value,
+};
+
+use crate::RelayContext;
+
+/// RelayStats is an async-graphql extension which counts the nodes fetched during a request and adds the counts to the 'relay' extension of the response.
+/// The fetches, nodes which couldn't be found and failed fetches are counted per type along with the number of nodes returned by the 'RelayNodeCache'.
+/// The 'relay' extension is left out of the response when the request didn't fetch any nodes.
+/// ```ignore
+/// let schema = Schema::build(QueryRoot::default(), EmptyMutation, EmptySubscription)
+///     .extension(RelayStats)
+///     .finish();
+/// // {"data": {...}, "extensions": {"relay": {"cacheHits": 1, "types": {"User": {"fetches": 2, "notFound": 1, "errors": 0}}}}}
+/// ```
+pub struct RelayStats;
+
+impl ExtensionFactory for RelayStats {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(RelayStatsExtension(RelayStatsRecorder::default()))
+    }
+}
+
+struct RelayStatsExtension(RelayStatsRecorder);
+
+#[async_trait::async_trait]
+impl Extension for RelayStatsExtension {
+    async fn prepare_request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        request: Request,
+        next: NextPrepareRequest<'_>,
+    ) -> ServerResult<Request> {
+        next.run(ctx, request.data(self.0.clone())).await
+    }
+
+    async fn request(&self, ctx: &ExtensionContext<'_>, next: NextRequest<'_>) -> Response {
+        let mut response = next.run(ctx).await;
+        if let Some(stats) = self.0.to_value() {
+            response.extensions.insert("relay".to_string(), stats);
+        }
+        response
+    }
+}
+
+#[derive(Default)]
+struct TypeStats {
+    fetches: u64,
+    not_found: u64,
+    errors: u64,
+}
+
+#[derive(Default)]
+struct Stats {
+    cache_hits: u64,
+    types: BTreeMap<&'static str, TypeStats>,
+}
+
+/// RelayStatsRecorder collects the statistics of a single request. It is stored in the request data by the 'RelayStats' extension.
+#[derive(Clone, Default)]
+pub(crate) struct RelayStatsRecorder(Arc<Mutex<Stats>>);
+
+impl RelayStatsRecorder {
+    fn with_stats(&self, f: impl FnOnce(&mut Stats)) {
+        f(&mut self.0.lock().unwrap_or_else(|err| err.into_inner()))
+    }
+
+    /// attach adds the recorder of the request, if there is one, to the RelayContext so the nodes fetched with it are counted.
+    pub(crate) fn attach(relay_ctx: &mut RelayContext, ctx: &Context<'_>) {
+        if let Some(recorder) = ctx.data_opt::<RelayStatsRecorder>() {
+            relay_ctx.insert(recorder.clone());
+        }
+    }
+
+    pub(crate) fn record_cache_hit(&self) {
+        self.with_stats(|stats| stats.cache_hits += 1);
+    }
+
+    pub(crate) fn record_fetches(
+        &self,
+        node_type: &'static str,
+        fetches: u64,
+        not_found: u64,
+        errors: u64,
+    ) {
+        self.with_stats(|stats| {
+            let stats = stats.types.entry(node_type).or_default();
+            stats.fetches += fetches;
+            stats.not_found += not_found;
+            stats.errors += errors;
+        });
+    }
+
+    /// to_value returns the statistics of the request or None if nothing was recorded.
+    fn to_value(&self) -> Option<Value> {
+        let stats = self.0.lock().unwrap_or_else(|err| err.into_inner());
+        if stats.cache_hits == 0 && stats.types.is_empty() {
+            return None;
+        }
+
+        let types = stats
+            .types
+            .iter()
+            .map(|(node_type, stats)| {
+                let mut value = IndexMap::new();
+                value.insert(Name::new("fetches"), Value::from(stats.fetches));
+                value.insert(Name::new("notFound"), Value::from(stats.not_found));
+                value.insert(Name::new("errors"), Value::from(stats.errors));
+                (Name::new(node_type), Value::Object(value))
+            })
+            .collect();
+
+        let mut value = IndexMap::new();
+        value.insert(Name::new("cacheHits"), Value::from(stats.cache_hits));
+        value.insert(Name::new("types"), Value::Object(types));
+        Some(Value::Object(value))
+    }
+}
+
+/// StatsHandle is the recorder of the current request, if the 'RelayStats' extension is enabled, used by the 'RelayInterface' macro.
+#[derive(Clone, Default)]
+pub struct StatsHandle(Option<RelayStatsRecorder>);
+
+impl StatsHandle {
+    /// from_relay_ctx gets the recorder stored in the RelayContext.
+    pub fn from_relay_ctx(ctx: &RelayContext) -> Self {
+        Self(ctx.get::<RelayStatsRecorder>().cloned())
+    }
+
+    /// from_ctx gets the recorder stored in the request data.
+    pub fn from_ctx(ctx: &Context<'_>) -> Self {
+        Self(ctx.data_opt::<RelayStatsRecorder>().cloned())
+    }
+
+    pub(crate) fn recorder(&self) -> Option<&RelayStatsRecorder> {
+        self.0.as_ref()
+    }
+}
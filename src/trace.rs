@@ -0,0 +1,273 @@
+use std::{any::type_name, future::Future};
+#[cfg(feature = "tracing")]
+use std::{cell::Cell, future::poll_fn, pin::pin, sync::Arc};
+
+#[cfg(feature = "tracing")]
+use async_graphql::{
+    extensions::{Extension, ExtensionContext, ExtensionFactory, NextExecute, NextSubscribe},
+    Response,
+};
+use async_graphql::{Error, ErrorExtensions};
+#[cfg(feature = "tracing")]
+use futures_util::stream::BoxStream;
+#[cfg(feature = "tracing")]
+use tracing::Instrument;
+
+use crate::{stats::StatsHandle, RelayError, RelayNode, RelayNodeID};
+
+#[cfg(feature = "tracing")]
+thread_local! {
+    static SHOW_IDS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// RelayTracing is an async-graphql extension which configures the spans and events emitted by the 'tracing' feature for the requests of a schema.
+/// Decoding a relay ID, dispatching it to a type and each call to 'RelayNode::get' or 'RelayNode::get_many' run inside a span carrying the type and relay ID of the node.
+/// Invalid ID's, nodes which can't be found and failed fetches are recorded as events.
+/// The relay ID's are recorded as '[redacted]' so they don't end up in the logs, unless the schema uses the extension with 'RelayTracing::with_ids'.
+/// ```ignore
+/// let schema = Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+///     .extension(RelayTracing::new().with_ids())
+///     .finish();
+/// ```
+#[cfg(feature = "tracing")]
+#[derive(Clone, Default)]
+pub struct RelayTracing {
+    ids: bool,
+}
+
+#[cfg(feature = "tracing")]
+impl RelayTracing {
+    /// new creates the default configuration which redacts the relay ID's.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// with_ids records the relay ID's in the spans instead of '[redacted]'.
+    pub fn with_ids(mut self) -> Self {
+        self.ids = true;
+        self
+    }
+
+    /// run applies the configuration while the future is polled, e.g. when calling 'fetch_node' outside of a request.
+    pub async fn run<F: Future>(&self, fut: F) -> F::Output {
+        run_with_ids(self.ids, fut).await
+    }
+}
+
+/// IdsGuard restores the previous configuration when a scope ends, even if it panics.
+#[cfg(feature = "tracing")]
+struct IdsGuard(bool);
+
+#[cfg(feature = "tracing")]
+impl Drop for IdsGuard {
+    fn drop(&mut self) {
+        SHOW_IDS.with(|show_ids| show_ids.set(self.0));
+    }
+}
+
+#[cfg(feature = "tracing")]
+fn scope_ids<R>(ids: bool, f: impl FnOnce() -> R) -> R {
+    let _guard = IdsGuard(SHOW_IDS.with(|show_ids| show_ids.replace(ids)));
+    f()
+}
+
+#[cfg(feature = "tracing")]
+async fn run_with_ids<F: Future>(ids: bool, fut: F) -> F::Output {
+    let mut fut = pin!(fut);
+    poll_fn(|cx| scope_ids(ids, || fut.as_mut().poll(cx))).await
+}
+
+#[cfg(feature = "tracing")]
+impl ExtensionFactory for RelayTracing {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(RelayTracingExtension(self.ids))
+    }
+}
+
+#[cfg(feature = "tracing")]
+struct RelayTracingExtension(bool);
+
+#[cfg(feature = "tracing")]
+#[async_trait::async_trait]
+impl Extension for RelayTracingExtension {
+    async fn execute(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        operation_name: Option<&str>,
+        next: NextExecute<'_>,
+    ) -> Response {
+        run_with_ids(self.0, next.run(ctx, operation_name)).await
+    }
+
+    fn subscribe<'s>(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        stream: BoxStream<'s, Response>,
+        next: NextSubscribe<'_>,
+    ) -> BoxStream<'s, Response> {
+        let ids = self.0;
+        let mut stream = next.run(ctx, stream);
+        Box::pin(futures_util::stream::poll_fn(move |cx| {
+            scope_ids(ids, || stream.as_mut().poll_next(cx))
+        }))
+    }
+}
+
+/// TraceSpan is the span a part of fetching a node runs in. It is empty when the 'tracing' feature is disabled.
+#[cfg(feature = "tracing")]
+pub type TraceSpan = tracing::Span;
+
+/// TraceSpan is the span a part of fetching a node runs in. It is empty when the 'tracing' feature is disabled.
+#[cfg(not(feature = "tracing"))]
+pub struct TraceSpan;
+
+#[cfg(feature = "tracing")]
+fn display_id(relay_id: &str) -> &str {
+    match SHOW_IDS.with(Cell::get) {
+        true => relay_id,
+        false => "[redacted]",
+    }
+}
+
+pub(crate) fn node_type<T>() -> &'static str {
+    let name = type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// fetch_node_span creates the span 'fetch_node' runs in.
+pub fn fetch_node_span(relay_id: &str) -> TraceSpan {
+    #[cfg(feature = "tracing")]
+    {
+        tracing::debug_span!("relay.fetch_node", relay.id = display_id(relay_id))
+    }
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = relay_id;
+        TraceSpan
+    }
+}
+
+/// fetch_nodes_span creates the span 'fetch_nodes' runs in.
+pub fn fetch_nodes_span(count: usize) -> TraceSpan {
+    #[cfg(feature = "tracing")]
+    {
+        tracing::debug_span!("relay.fetch_nodes", relay.count = count)
+    }
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = count;
+        TraceSpan
+    }
+}
+
+/// get_span creates the span 'RelayNode::get' runs in.
+fn get_span<T: RelayNode>(id: &RelayNodeID<T>) -> TraceSpan {
+    #[cfg(feature = "tracing")]
+    {
+        tracing::debug_span!(
+            "relay.get",
+            relay.r#type = node_type::<T>(),
+            relay.id = display_id(&id.to_relay_id().unwrap_or_default())
+        )
+    }
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = id;
+        TraceSpan
+    }
+}
+
+/// get_many_span creates the span 'RelayNode::get_many' runs in.
+pub(crate) fn get_many_span<T: RelayNode>(ids: &[RelayNodeID<T>]) -> TraceSpan {
+    #[cfg(feature = "tracing")]
+    {
+        tracing::debug_span!(
+            "relay.get_many",
+            relay.r#type = node_type::<T>(),
+            relay.count = ids.len()
+        )
+    }
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = ids;
+        TraceSpan
+    }
+}
+
+/// traced runs the future inside the span.
+pub async fn traced<F: Future>(span: TraceSpan, fut: F) -> F::Output {
+    #[cfg(feature = "tracing")]
+    {
+        fut.instrument(span).await
+    }
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = span;
+        fut.await
+    }
+}
+
+/// traced_get runs the fetch of a node inside the span and records an event when the node can't be found or the fetch fails.
+async fn traced_get<N, F>(span: TraceSpan, fut: F) -> Result<Option<N>, Error>
+where
+    F: Future<Output = Result<Option<N>, Error>>,
+{
+    #[cfg(feature = "tracing")]
+    {
+        let result = fut.instrument(span.clone()).await;
+        let _entered = span.enter();
+        match &result {
+            Ok(Some(_)) => {}
+            Ok(None) => tracing::debug!("relay node not found"),
+            Err(err) => tracing::warn!(error = %err.message, "relay node fetch failed"),
+        }
+        result
+    }
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = span;
+        fut.await
+    }
+}
+
+/// NodeObserver records the span and the 'RelayStats' of fetching a single node.
+pub struct NodeObserver {
+    span: TraceSpan,
+    stats: StatsHandle,
+    node_type: &'static str,
+}
+
+impl NodeObserver {
+    /// new creates an observer for fetching the node with the ID.
+    pub fn new<T: RelayNode>(stats: StatsHandle, id: &RelayNodeID<T>) -> Self {
+        Self {
+            span: get_span(id),
+            stats,
+            node_type: node_type::<T>(),
+        }
+    }
+
+    /// observe runs the fetch of the node and records its outcome.
+    pub async fn observe<N, F>(self, fut: F) -> Result<Option<N>, Error>
+    where
+        F: Future<Output = Result<Option<N>, Error>>,
+    {
+        let result = traced_get(self.span, fut).await;
+        if let Some(recorder) = self.stats.recorder() {
+            let (not_found, errors) = match &result {
+                Ok(Some(_)) => (0, 0),
+                Ok(None) => (1, 0),
+                Err(_) => (0, 1),
+            };
+            recorder.record_fetches(self.node_type, 1, not_found, errors);
+        }
+        result
+    }
+}
+
+/// relay_error records an event for an invalid relay ID and converts it into an error with the 'code' extension.
+pub fn relay_error(err: RelayError) -> Error {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(code = err.code(), error = %err, "invalid relay id");
+    err.extend()
+}
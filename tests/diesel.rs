@@ -0,0 +1,93 @@
+#![cfg(feature = "diesel")]
+
+use async_graphql::Error;
+use async_graphql_relay::{RelayContext, RelayNode, RelayNodeID, RelayNodeObject};
+use diesel::{prelude::*, sqlite::Sqlite};
+
+#[derive(RelayNodeObject)]
+#[relay(node_suffix = "p", key = "i32")]
+pub struct Post;
+
+impl RelayNode for Post {
+    async fn get(_ctx: RelayContext, _id: RelayNodeID<Self>) -> Result<Option<Self>, Error> {
+        Ok(None)
+    }
+}
+
+#[derive(RelayNodeObject)]
+#[relay(node_suffix = "u", key = "String")]
+pub struct User;
+
+impl RelayNode for User {
+    async fn get(_ctx: RelayContext, _id: RelayNodeID<Self>) -> Result<Option<Self>, Error> {
+        Ok(None)
+    }
+}
+
+#[derive(RelayNodeObject)]
+#[relay(node_suffix = "c", key = "i16")]
+pub struct Category;
+
+impl RelayNode for Category {
+    async fn get(_ctx: RelayContext, _id: RelayNodeID<Self>) -> Result<Option<Self>, Error> {
+        Ok(None)
+    }
+}
+
+diesel::table! {
+    posts (id) {
+        id -> Integer,
+        author_id -> Text,
+        category_id -> SmallInt,
+        title -> Text,
+    }
+}
+
+#[derive(Debug, PartialEq, Queryable, Selectable, Insertable)]
+#[diesel(table_name = posts, check_for_backend(Sqlite))]
+struct PostRow {
+    id: RelayNodeID<Post>,
+    author_id: RelayNodeID<User>,
+    category_id: RelayNodeID<Category>,
+    title: String,
+}
+
+fn connection() -> SqliteConnection {
+    let mut conn = SqliteConnection::establish(":memory:").unwrap();
+    diesel::sql_query(
+        "CREATE TABLE posts (id INTEGER PRIMARY KEY, author_id TEXT NOT NULL, category_id SMALLINT NOT NULL, title TEXT NOT NULL)",
+    )
+    .execute(&mut conn)
+    .unwrap();
+    conn
+}
+
+#[test]
+fn bind_and_load() {
+    let mut conn = connection();
+    let row = PostRow {
+        id: RelayNodeID::new(42),
+        author_id: RelayNodeID::new("oscar".to_string()),
+        category_id: RelayNodeID::new(7),
+        title: "Hello".to_string(),
+    };
+
+    diesel::insert_into(posts::table)
+        .values(&row)
+        .execute(&mut conn)
+        .unwrap();
+
+    let loaded = posts::table
+        .find(&row.id)
+        .select(PostRow::as_select())
+        .first(&mut conn)
+        .unwrap();
+    assert_eq!(loaded, row);
+
+    let ids = posts::table
+        .filter(posts::author_id.eq(&row.author_id))
+        .select(posts::id)
+        .load::<RelayNodeID<Post>>(&mut conn)
+        .unwrap();
+    assert_eq!(ids, vec![row.id]);
+}
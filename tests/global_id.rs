@@ -0,0 +1,182 @@
+use std::convert::TryFrom;
+
+use async_graphql::{
+    value, EmptyMutation, EmptySubscription, Error, ErrorExtensions, Interface, Object, Schema,
+    ServerError, SimpleObject, Value,
+};
+use async_graphql_relay::{RelayContext, RelayInterface, RelayNode, RelayNodeID, RelayNodeObject};
+
+const USER_ID: &str = "92ba0c2d4b4e4e2991dd8f96a078c3ffu";
+
+#[derive(Debug, Clone, SimpleObject, RelayNodeObject)]
+#[relay(node_suffix = "u")]
+pub struct User {
+    pub id: RelayNodeID<User>,
+}
+
+impl RelayNode for User {
+    async fn get(_ctx: RelayContext, _id: RelayNodeID<Self>) -> Result<Option<Self>, Error> {
+        Ok(None)
+    }
+}
+
+#[derive(Debug, Clone, SimpleObject, RelayNodeObject)]
+#[relay(node_suffix = "p")]
+pub struct Post {
+    pub id: RelayNodeID<Post>,
+}
+
+impl RelayNode for Post {
+    async fn get(_ctx: RelayContext, _id: RelayNodeID<Self>) -> Result<Option<Self>, Error> {
+        Ok(None)
+    }
+}
+
+#[derive(Debug, Interface, RelayInterface)]
+#[graphql(field(name = "id", ty = "NodeGlobalID"))]
+pub enum Node {
+    User(User),
+    Post(Post),
+}
+
+pub struct Query;
+
+#[Object]
+impl Query {
+    async fn echo(&self, id: NodeGlobalID) -> NodeGlobalID {
+        id
+    }
+
+    async fn describe(&self, id: NodeGlobalID) -> Result<String, Error> {
+        Ok(match NodeTypedID::try_from(id)? {
+            NodeTypedID::User(id) => format!("user {}", id.key()),
+            NodeTypedID::Post(id) => format!("post {}", id.key()),
+        })
+    }
+
+    async fn user_key(&self, id: NodeGlobalID) -> Result<String, Error> {
+        let id = RelayNodeID::<User>::try_from(&id).map_err(|err| err.extend())?;
+        Ok(id.key().to_string())
+    }
+}
+
+fn schema() -> Schema<Query, EmptyMutation, EmptySubscription> {
+    Schema::new(Query, EmptyMutation, EmptySubscription)
+}
+
+fn code(err: &ServerError) -> Option<&Value> {
+    err.extensions.as_ref().and_then(|ext| ext.get("code"))
+}
+
+#[tokio::test]
+async fn global_id_argument_accepts_the_ids_of_the_interface() {
+    let post_id = "92ba0c2d4b4e4e2991dd8f96a078c3ffp";
+    for id in &[USER_ID, post_id] {
+        let response = schema()
+            .execute(format!(r#"{{ echo(id: "{}") }}"#, id))
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(response.data, value!({ "echo": *id }));
+    }
+}
+
+#[tokio::test]
+async fn global_id_argument_rejects_invalid_ids() {
+    let cases = [
+        ("92ba0c2d4b4e4e2991dd8f96a078c3ffx", "UNKNOWN_TYPE"),
+        ("invalid", "INVALID_FORMAT"),
+    ];
+    for (id, expected) in &cases {
+        let response = schema()
+            .execute(format!(r#"{{ echo(id: "{}") }}"#, id))
+            .await;
+        assert_eq!(response.errors.len(), 1, "{:?}", response.errors);
+        assert_eq!(code(&response.errors[0]), Some(&Value::from(*expected)));
+    }
+
+    let response = schema().execute("{ echo(id: 42) }").await;
+    assert_eq!(response.errors.len(), 1, "{:?}", response.errors);
+}
+
+#[tokio::test]
+async fn typed_id_dispatches_to_the_type() {
+    let response = schema()
+        .execute(format!(
+            r#"{{ user: describe(id: "{}") post: describe(id: "92ba0c2d4b4e4e2991dd8f96a078c3ffp") }}"#,
+            USER_ID
+        ))
+        .await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(
+        response.data,
+        value!({
+            "user": "user 92ba0c2d-4b4e-4e29-91dd-8f96a078c3ff",
+            "post": "post 92ba0c2d-4b4e-4e29-91dd-8f96a078c3ff",
+        })
+    );
+}
+
+#[tokio::test]
+async fn global_id_converts_into_the_relay_node_id_of_its_type() {
+    let response = schema()
+        .execute(format!(r#"{{ userKey(id: "{}") }}"#, USER_ID))
+        .await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(
+        response.data,
+        value!({ "userKey": "92ba0c2d-4b4e-4e29-91dd-8f96a078c3ff" })
+    );
+
+    let response = schema()
+        .execute(r#"{ userKey(id: "92ba0c2d4b4e4e2991dd8f96a078c3ffp") }"#)
+        .await;
+    assert_eq!(response.errors.len(), 1, "{:?}", response.errors);
+    assert_eq!(
+        response.errors[0].message,
+        "Expected an id of type 'u' but got an id of type 'p'!"
+    );
+    assert_eq!(
+        code(&response.errors[0]),
+        Some(&Value::from("TYPE_MISMATCH"))
+    );
+}
+
+#[cfg(feature = "signed")]
+mod signed {
+    use async_graphql_relay::{RelayError, RelayKeyRing, RelayKeys, SignedCodec};
+
+    use super::*;
+
+    #[derive(Debug, Clone, SimpleObject, RelayNodeObject)]
+    #[relay(node_suffix = "s", codec = "SignedCodec")]
+    pub struct Secret {
+        pub id: RelayNodeID<Secret>,
+    }
+
+    impl RelayNode for Secret {
+        async fn get(_ctx: RelayContext, _id: RelayNodeID<Self>) -> Result<Option<Self>, Error> {
+            Ok(None)
+        }
+    }
+
+    #[derive(Debug, Interface, RelayInterface)]
+    #[graphql(field(name = "id", ty = "SignedNodeGlobalID"))]
+    #[relay(codec = "SignedCodec")]
+    pub enum SignedNode {
+        Secret(Secret),
+    }
+
+    #[test]
+    fn to_global_id_returns_an_error_without_keys() {
+        let id = SignedNodeTypedID::from(
+            RelayNodeID::<Secret>::new_from_str("92ba0c2d-4b4e-4e29-91dd-8f96a078c3ff").unwrap(),
+        );
+        assert_eq!(id.to_global_id().err(), Some(RelayError::MissingKeys));
+
+        let keys = RelayKeys::new().with_signing_keys(RelayKeyRing::new("secret"));
+        keys.scope(|| {
+            let global_id = id.to_global_id().unwrap();
+            assert_eq!(SignedNodeTypedID::try_from(global_id), Ok(id.clone()));
+        });
+    }
+}
@@ -0,0 +1,55 @@
+use async_graphql_relay::{RelayNodeID, RelayNodeObject, RelayNodeStruct};
+
+#[derive(RelayNodeObject)]
+#[relay(node_suffix = "p", key = "i64")]
+pub struct Post;
+
+#[derive(RelayNodeObject)]
+#[relay(node_suffix = "c", key = "String")]
+pub struct Comment;
+
+#[derive(RelayNodeObject)]
+#[relay(node_suffix = "u")]
+pub struct User;
+
+macro_rules! impl_relay_node {
+    ($($ty:ty),*) => {
+        $(impl async_graphql_relay::RelayNode for $ty {
+            async fn get(
+                _ctx: async_graphql_relay::RelayContext,
+                _id: RelayNodeID<Self>,
+            ) -> Result<Option<Self>, async_graphql::Error> {
+                Ok(None)
+            }
+        })*
+    };
+}
+
+impl_relay_node!(Post, Comment, User);
+
+#[test]
+fn custom_keys_round_trip() {
+    let id = RelayNodeID::<Post>::new(42);
+    let decoded = RelayNodeID::<Post>::new_from_relay_id(id.to_string()).unwrap();
+    assert_eq!(*decoded.key(), 42);
+
+    let id = RelayNodeID::<Comment>::new("hello:world".to_string());
+    let decoded = RelayNodeID::<Comment>::new_from_relay_id(id.to_string()).unwrap();
+    assert_eq!(decoded.key(), "hello:world");
+}
+
+#[test]
+fn custom_keys_default_to_base64() {
+    assert_eq!(<Post as RelayNodeStruct>::ID_SUFFIX, "p");
+    assert_eq!(RelayNodeID::<Post>::new(42).to_string(), "cDo0Mg");
+}
+
+#[test]
+fn uuid_keys_default_to_suffix() {
+    let id = RelayNodeID::<User>::new_from_str("92ba0c2d-4b4e-4e29-91dd-8f96a078c3ff").unwrap();
+    assert_eq!(id.to_string(), "92ba0c2d4b4e4e2991dd8f96a078c3ffu");
+    assert_eq!(
+        RelayNodeID::<User>::new_from_relay_id(id.to_string()).unwrap(),
+        id
+    );
+}
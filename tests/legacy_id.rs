@@ -0,0 +1,110 @@
+use async_graphql::{Error, InputValueResult, Pos, ScalarType, Value};
+use async_graphql_relay::{
+    uuid::Uuid, Base64Codec, RelayContext, RelayIdCodec, RelayNode, RelayNodeID, RelayNodeObject,
+};
+
+const KEY: &str = "92ba0c2d4b4e4e2991dd8f96a078c3ff";
+
+/// User accepts the bare UUID as input.
+#[derive(RelayNodeObject)]
+#[relay(node_suffix = "u", legacy_id_input)]
+pub struct User;
+
+impl RelayNode for User {
+    async fn get(_ctx: RelayContext, _id: RelayNodeID<Self>) -> Result<Option<Self>, Error> {
+        Ok(None)
+    }
+}
+
+/// Post only accepts its relay ID as input.
+#[derive(RelayNodeObject)]
+#[relay(node_suffix = "p")]
+pub struct Post;
+
+impl RelayNode for Post {
+    async fn get(_ctx: RelayContext, _id: RelayNodeID<Self>) -> Result<Option<Self>, Error> {
+        Ok(None)
+    }
+}
+
+/// Comment accepts its bare String key as input.
+#[derive(RelayNodeObject)]
+#[relay(node_suffix = "c", key = "String", legacy_id_input)]
+pub struct Comment;
+
+impl RelayNode for Comment {
+    async fn get(_ctx: RelayContext, _id: RelayNodeID<Self>) -> Result<Option<Self>, Error> {
+        Ok(None)
+    }
+}
+
+fn parse<T: RelayNode>(input: &str) -> InputValueResult<RelayNodeID<T>> {
+    <RelayNodeID<T> as ScalarType>::parse(Value::from(input))
+}
+
+fn code<T: RelayNode>(result: InputValueResult<RelayNodeID<T>>) -> Option<Value> {
+    let err = result.err().unwrap().into_server_error(Pos::default());
+    err.extensions.and_then(|ext| ext.get("code").cloned())
+}
+
+#[test]
+fn legacy_input_accepts_the_bare_key() {
+    let expected = RelayNodeID::<User>::new(Uuid::parse_str(KEY).unwrap());
+    assert_eq!(parse::<User>(KEY).unwrap(), expected);
+    assert_eq!(parse::<User>(&format!("{}u", KEY)).unwrap(), expected);
+
+    let comment = parse::<Comment>("first-comment").unwrap();
+    assert_eq!(comment.key(), "first-comment");
+}
+
+#[test]
+fn legacy_input_rejects_relay_ids_of_other_types() {
+    assert_eq!(
+        code(parse::<User>(&format!("{}p", KEY))),
+        Some(Value::from("TYPE_MISMATCH"))
+    );
+
+    let user_id = Base64Codec::encode("u", KEY).unwrap();
+    assert_eq!(
+        code(parse::<Comment>(&user_id)),
+        Some(Value::from("TYPE_MISMATCH"))
+    );
+}
+
+#[test]
+fn strict_input_rejects_the_bare_key() {
+    assert_eq!(
+        code(parse::<Post>(KEY)),
+        Some(Value::from("INVALID_FORMAT"))
+    );
+    assert!(parse::<Post>(&format!("{}p", KEY)).is_ok());
+}
+
+#[cfg(feature = "signed")]
+mod signed {
+    use async_graphql_relay::{RelayKeyRing, RelayKeys, SignedCodec};
+
+    use super::*;
+
+    /// Secret uses a tamper proof codec so the bare key is never accepted.
+    #[derive(RelayNodeObject)]
+    #[relay(node_suffix = "s", codec = "SignedCodec", legacy_id_input)]
+    pub struct Secret;
+
+    impl RelayNode for Secret {
+        async fn get(_ctx: RelayContext, _id: RelayNodeID<Self>) -> Result<Option<Self>, Error> {
+            Ok(None)
+        }
+    }
+
+    #[test]
+    fn legacy_input_is_ignored_for_tamper_proof_codecs() {
+        let keys = RelayKeys::new().with_signing_keys(RelayKeyRing::new("secret"));
+        keys.scope(|| {
+            let id = RelayNodeID::<Secret>::new(Uuid::parse_str(KEY).unwrap());
+            assert_eq!(parse::<Secret>(&id.to_relay_id().unwrap()).unwrap(), id);
+            assert!(parse::<Secret>(KEY).is_err());
+            assert!(parse::<Secret>(&format!("{}s", KEY)).is_err());
+        });
+    }
+}
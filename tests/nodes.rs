@@ -0,0 +1,212 @@
+use async_graphql::{Error, Interface, SimpleObject, Value};
+use async_graphql_relay::{
+    RelayContext, RelayInterface, RelayNode, RelayNodeID, RelayNodeInterface, RelayNodeObject,
+};
+
+const MISSING: &str = "00000000-0000-0000-0000-000000000000";
+
+#[derive(Debug, Clone, SimpleObject, RelayNodeObject)]
+#[relay(node_suffix = "u")]
+pub struct User {
+    pub id: RelayNodeID<User>,
+}
+
+impl RelayNode for User {
+    async fn get(_ctx: RelayContext, id: RelayNodeID<Self>) -> Result<Option<Self>, Error> {
+        Ok(match id.to_uuid().is_nil() {
+            true => None,
+            false => Some(User { id }),
+        })
+    }
+}
+
+#[derive(Debug, Clone, SimpleObject, RelayNodeObject)]
+#[relay(node_suffix = "t")]
+pub struct Tenant {
+    pub id: RelayNodeID<Tenant>,
+}
+
+impl RelayNode for Tenant {
+    async fn get(_ctx: RelayContext, id: RelayNodeID<Self>) -> Result<Option<Self>, Error> {
+        Ok(Some(Tenant { id }))
+    }
+
+    async fn get_many(
+        _ctx: RelayContext,
+        ids: Vec<RelayNodeID<Self>>,
+    ) -> Result<Vec<Option<Self>>, Error> {
+        Ok(ids.into_iter().map(|id| Some(Tenant { id })).collect())
+    }
+}
+
+/// Team returns a single node from 'get_many' regardless of the number of ID's.
+#[derive(Debug, Clone, SimpleObject, RelayNodeObject)]
+#[relay(node_suffix = "m")]
+pub struct Team {
+    pub id: RelayNodeID<Team>,
+}
+
+impl RelayNode for Team {
+    async fn get(_ctx: RelayContext, id: RelayNodeID<Self>) -> Result<Option<Self>, Error> {
+        Ok(Some(Team { id }))
+    }
+
+    async fn get_many(
+        _ctx: RelayContext,
+        mut ids: Vec<RelayNodeID<Self>>,
+    ) -> Result<Vec<Option<Self>>, Error> {
+        ids.truncate(1);
+        Ok(ids.into_iter().map(|id| Some(Team { id })).collect())
+    }
+}
+
+#[derive(Debug, Interface, RelayInterface)]
+#[graphql(field(name = "id", ty = "NodeGlobalID"))]
+#[relay(max_nodes = 6)]
+pub enum Node {
+    User(User),
+    Tenant(Tenant),
+    Team(Team),
+}
+
+fn relay_id(uuid: &str, suffix: &str) -> String {
+    format!("{}{}", uuid.replace('-', ""), suffix)
+}
+
+fn user(index: u8) -> String {
+    relay_id(
+        &format!("92ba0c2d-4b4e-4e29-91dd-8f96a078c3{:02x}", index),
+        "u",
+    )
+}
+
+fn tenant(index: u8) -> String {
+    relay_id(
+        &format!("92ba0c2d-4b4e-4e29-91dd-8f96a078c3{:02x}", index),
+        "t",
+    )
+}
+
+fn team(index: u8) -> String {
+    relay_id(
+        &format!("92ba0c2d-4b4e-4e29-91dd-8f96a078c3{:02x}", index),
+        "m",
+    )
+}
+
+fn describe(result: &Result<Option<Node>, Error>) -> String {
+    match result {
+        Ok(Some(Node::User(user))) => user.id.to_string(),
+        Ok(Some(Node::Tenant(tenant))) => tenant.id.to_string(),
+        Ok(Some(Node::Team(team))) => team.id.to_string(),
+        Ok(None) => "null".to_string(),
+        Err(err) => format!("error: {}", err.message),
+    }
+}
+
+#[tokio::test]
+async fn fetch_nodes_keeps_the_order_across_types() {
+    let ids = vec![tenant(1), user(2), tenant(3), user(4), user(5), tenant(6)];
+    let results = Node::fetch_nodes(RelayContext::nil(), ids.clone())
+        .await
+        .unwrap();
+    assert_eq!(results.iter().map(describe).collect::<Vec<_>>(), ids);
+}
+
+#[tokio::test]
+async fn fetch_nodes_returns_errors_and_nulls_per_id() {
+    let ids = vec![
+        user(1),
+        "invalid".to_string(),
+        relay_id(MISSING, "u"),
+        tenant(2),
+        relay_id(MISSING, "x"),
+        user(3),
+    ];
+    let results = Node::fetch_nodes(RelayContext::nil(), ids.clone())
+        .await
+        .unwrap();
+    assert_eq!(
+        results.iter().map(describe).collect::<Vec<_>>(),
+        vec![
+            user(1),
+            "error: Invalid id provided to node query!".to_string(),
+            "null".to_string(),
+            tenant(2),
+            "error: Unknown type 'x' in the provided id!".to_string(),
+            user(3),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn fetch_nodes_rejects_get_many_results_of_the_wrong_length() {
+    let ids = vec![team(1), user(2), team(3)];
+    let results = Node::fetch_nodes(RelayContext::nil(), ids).await.unwrap();
+    assert_eq!(describe(&results[1]), user(2));
+    for result in [&results[0], &results[2]] {
+        let err = result.as_ref().err().unwrap();
+        assert_eq!(err.message, "'Team::get_many' returned 1 nodes for 2 ids!");
+        let code = err.extensions.as_ref().and_then(|ext| ext.get("code"));
+        assert_eq!(code, Some(&Value::from("BACKEND")));
+    }
+}
+
+#[tokio::test]
+async fn fetch_nodes_handles_empty_input() {
+    let results = Node::fetch_nodes(RelayContext::nil(), Vec::new())
+        .await
+        .unwrap();
+    assert!(results.is_empty());
+}
+
+#[tokio::test]
+async fn fetch_nodes_limits_the_number_of_ids() {
+    let ids = (0..6).map(user).collect::<Vec<_>>();
+    assert_eq!(
+        Node::fetch_nodes(RelayContext::nil(), ids)
+            .await
+            .unwrap()
+            .len(),
+        6
+    );
+
+    let ids = (0..7).map(user).collect::<Vec<_>>();
+    let err = Node::fetch_nodes(RelayContext::nil(), ids)
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(
+        err.message,
+        "A maximum of 6 ids can be provided to the nodes query!"
+    );
+    let extensions = err.extensions.unwrap();
+    assert_eq!(extensions.get("code"), Some(&Value::from("TOO_MANY_IDS")));
+    assert_eq!(extensions.get("max"), Some(&Value::from(6)));
+}
+
+#[tokio::test]
+async fn fetch_node_returns_null_for_missing_nodes() {
+    let node = Node::fetch_node(RelayContext::nil(), user(1))
+        .await
+        .unwrap();
+    assert!(matches!(node, Some(Node::User(_))));
+    let node = Node::fetch_node(RelayContext::nil(), relay_id(MISSING, "u"))
+        .await
+        .unwrap();
+    assert!(node.is_none());
+}
+
+#[cfg(feature = "dataloader")]
+#[tokio::test]
+async fn loader_rejects_get_many_results_of_the_wrong_length() {
+    use async_graphql::dataloader::DataLoader;
+    use async_graphql_relay::RelayNodeLoader;
+
+    let loader = DataLoader::new(RelayNodeLoader::new(RelayContext::nil()), tokio::spawn);
+    let ids = vec![team(1), team(3)]
+        .into_iter()
+        .map(|id| RelayNodeID::<Team>::new_from_relay_id(id).unwrap());
+    let err = loader.load_many(ids).await.err().unwrap();
+    assert_eq!(err.message, "'Team::get_many' returned 1 nodes for 2 ids!");
+}
@@ -0,0 +1,151 @@
+use async_graphql::{
+    value, Context, EmptyMutation, EmptySubscription, Error, Interface, MergedObject, Object,
+    PathSegment, Schema, SimpleObject, Value,
+};
+use async_graphql_relay::{
+    RelayContext, RelayInterface, RelayNode, RelayNodeID, RelayNodeObject, RelayNodeQuery,
+};
+
+const TENANT_ID: &str = "92ba0c2d4b4e4e2991dd8f96a078c3fft";
+const SECRET_ID: &str = "0f2b6a4e8c1d4b7e9a3f5c6d7e8f9a0bs";
+
+/// Tenant can only be fetched with the async-graphql Context.
+#[derive(Debug, Clone, SimpleObject, RelayNodeObject)]
+#[relay(node_suffix = "t")]
+pub struct Tenant {
+    pub id: RelayNodeID<Tenant>,
+    pub name: String,
+}
+
+impl RelayNode for Tenant {
+    async fn get(_ctx: RelayContext, _id: RelayNodeID<Self>) -> Result<Option<Self>, Error> {
+        Err(Error::new(
+            "Tenant can only be fetched with the async-graphql Context!",
+        ))
+    }
+
+    async fn get_ctx(ctx: &Context<'_>, id: RelayNodeID<Self>) -> Result<Option<Self>, Error> {
+        Ok(Some(Tenant {
+            id,
+            name: ctx.data::<String>()?.clone(),
+        }))
+    }
+}
+
+/// Secret is only visible to requests which have been granted access in the request data.
+#[derive(Debug, Clone, SimpleObject, RelayNodeObject)]
+#[relay(node_suffix = "s")]
+pub struct Secret {
+    pub id: RelayNodeID<Secret>,
+    pub value: String,
+}
+
+pub struct Granted;
+
+impl RelayNode for Secret {
+    async fn get(_ctx: RelayContext, _id: RelayNodeID<Self>) -> Result<Option<Self>, Error> {
+        Err(Error::new(
+            "Secret can only be fetched with the async-graphql Context!",
+        ))
+    }
+
+    async fn authorize_ctx(ctx: &Context<'_>, _id: &RelayNodeID<Self>) -> Result<bool, Error> {
+        Ok(ctx.data_opt::<Granted>().is_some())
+    }
+
+    async fn get_ctx(_ctx: &Context<'_>, id: RelayNodeID<Self>) -> Result<Option<Self>, Error> {
+        Ok(Some(Secret {
+            id,
+            value: "hunter2".to_string(),
+        }))
+    }
+}
+
+#[derive(Debug, Interface, RelayInterface)]
+#[graphql(field(name = "id", ty = "NodeGlobalID"))]
+pub enum Node {
+    Tenant(Tenant),
+    Secret(Secret),
+}
+
+#[derive(Default)]
+pub struct Query;
+
+#[Object]
+impl Query {
+    async fn version(&self) -> i32 {
+        1
+    }
+}
+
+#[derive(MergedObject, Default)]
+pub struct QueryRoot(Query, RelayNodeQuery<Node>);
+
+fn schema() -> Schema<QueryRoot, EmptyMutation, EmptySubscription> {
+    Schema::build(QueryRoot::default(), EmptyMutation, EmptySubscription)
+        .data("My Company".to_string())
+        .finish()
+}
+
+#[tokio::test]
+async fn node_and_nodes_use_get_ctx() {
+    let query = format!(
+        r#"{{ node(id: "{id}") {{ ... on Tenant {{ name }} }} nodes(ids: ["{id}", "{id}"]) {{ ... on Tenant {{ name }} }} }}"#,
+        id = TENANT_ID
+    );
+    let response = schema().execute(query).await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(
+        response.data,
+        value!({
+            "node": { "name": "My Company" },
+            "nodes": [{ "name": "My Company" }, { "name": "My Company" }],
+        })
+    );
+}
+
+fn secret_query() -> String {
+    format!(
+        r#"{{ node(id: "{secret}") {{ ... on Secret {{ value }} }} nodes(ids: ["{tenant}", "{secret}"]) {{ ... on Tenant {{ name }} ... on Secret {{ value }} }} }}"#,
+        tenant = TENANT_ID,
+        secret = SECRET_ID
+    )
+}
+
+#[tokio::test]
+async fn node_and_nodes_use_authorize_ctx() {
+    let response = schema().execute(secret_query()).await;
+    assert_eq!(
+        response.data,
+        value!({
+            "nodes": [{ "name": "My Company" }, null],
+        })
+    );
+    assert_eq!(response.errors.len(), 2, "{:?}", response.errors);
+    for err in &response.errors {
+        let code = err.extensions.as_ref().and_then(|ext| ext.get("code"));
+        assert_eq!(code, Some(&Value::from("UNAUTHORIZED")));
+    }
+    assert_eq!(
+        response.errors[0].path,
+        vec![PathSegment::Field("node".to_string())]
+    );
+    assert_eq!(
+        response.errors[1].path,
+        vec![
+            PathSegment::Field("nodes".to_string()),
+            PathSegment::Index(1)
+        ]
+    );
+
+    let request = async_graphql::Request::new(secret_query()).data(Granted);
+    let response = schema().execute(request).await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(
+        response.data,
+        value!({
+            "node": { "value": "hunter2" },
+            "nodes": [{ "name": "My Company" }, { "value": "hunter2" }],
+        })
+    );
+}
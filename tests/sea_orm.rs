@@ -0,0 +1,59 @@
+#![cfg(feature = "sea-orm")]
+
+use async_graphql::Error;
+use async_graphql_relay::{RelayContext, RelayNode, RelayNodeID, RelayNodeObject};
+use sea_orm::{DatabaseBackend, EntityTrait, MockDatabase, Transaction};
+
+#[derive(RelayNodeObject)]
+#[relay(node_suffix = "p")]
+pub struct Post;
+
+impl RelayNode for Post {
+    async fn get(_ctx: RelayContext, _id: RelayNodeID<Self>) -> Result<Option<Self>, Error> {
+        Ok(None)
+    }
+}
+
+mod post {
+    use std::convert::TryInto;
+
+    use async_graphql_relay::RelayNodeID;
+    use sea_orm::entity::prelude::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    #[sea_orm(table_name = "posts")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub id: RelayNodeID<super::Post>,
+        pub title: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+#[tokio::test]
+async fn bind_and_load_the_primary_key() {
+    let id = RelayNodeID::<Post>::new_from_str("92ba0c2d-4b4e-4e29-91dd-8f96a078c3ff").unwrap();
+    let model = post::Model {
+        id: id.clone(),
+        title: "Hello".to_string(),
+    };
+    let db = MockDatabase::new(DatabaseBackend::Postgres)
+        .append_query_results([vec![model.clone()]])
+        .into_connection();
+
+    let found = post::Entity::find_by_id(id.clone()).one(&db).await.unwrap();
+    assert_eq!(found, Some(model));
+
+    assert_eq!(
+        db.into_transaction_log(),
+        vec![Transaction::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r#"SELECT "posts"."id", "posts"."title" FROM "posts" WHERE "posts"."id" = $1 LIMIT $2"#,
+            [id.to_uuid().into(), 1u64.into()],
+        )]
+    );
+}
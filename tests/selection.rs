@@ -0,0 +1,248 @@
+use std::sync::{Arc, Mutex};
+
+use async_graphql::{
+    value, EmptyMutation, EmptySubscription, Error, Interface, Request, Schema, SimpleObject,
+};
+use async_graphql_relay::{
+    RelayContext, RelayInterface, RelayNode, RelayNodeCache, RelayNodeID, RelayNodeObject,
+    RelayNodeQuery, RelayNodeSelection,
+};
+
+const POST_ID: &str = "92ba0c2d4b4e4e2991dd8f96a078c3ffp";
+const OTHER_POST_ID: &str = "0f2b6a4e8c1d4b7e9a3f5c6d7e8f9a0bp";
+
+/// Call is the sorted selected fields and the number of ID's passed to 'get' or 'get_many'.
+type Call = (Vec<String>, usize);
+
+/// Calls records every call to 'get' and 'get_many'.
+#[derive(Clone, Default)]
+pub struct Calls(Arc<Mutex<Vec<Call>>>);
+
+impl Calls {
+    fn record(ctx: &RelayContext, ids: usize) {
+        let mut fields = ctx
+            .get::<RelayNodeSelection>()
+            .map(|selection| selection.fields().map(str::to_string).collect::<Vec<_>>())
+            .unwrap_or_default();
+        fields.sort();
+        if let Some(calls) = ctx.get::<Calls>() {
+            calls.0.lock().unwrap().push((fields, ids));
+        }
+    }
+
+    fn take(&self) -> Vec<Call> {
+        let mut calls = std::mem::take(&mut *self.0.lock().unwrap());
+        calls.sort();
+        calls
+    }
+}
+
+fn fields(fields: &[&str], ids: usize) -> Call {
+    (fields.iter().map(|field| field.to_string()).collect(), ids)
+}
+
+/// Selections records the selection passed to every call of 'get'.
+#[derive(Clone, Default)]
+pub struct Selections(Arc<Mutex<Vec<RelayNodeSelection>>>);
+
+#[derive(Debug, Clone, Default, SimpleObject)]
+pub struct Profile {
+    pub name: String,
+    pub bio: String,
+}
+
+#[derive(Debug, Clone, Default, SimpleObject)]
+pub struct Author {
+    pub profile: Profile,
+}
+
+#[derive(Debug, Clone, SimpleObject, RelayNodeObject)]
+#[relay(node_suffix = "p")]
+pub struct Post {
+    pub id: RelayNodeID<Post>,
+    pub title: String,
+    pub body: String,
+    pub author: Author,
+}
+
+impl Post {
+    fn new(id: RelayNodeID<Post>) -> Self {
+        Post {
+            id,
+            title: "Hello".to_string(),
+            body: "World".to_string(),
+            author: Author::default(),
+        }
+    }
+}
+
+impl RelayNode for Post {
+    async fn get(ctx: RelayContext, id: RelayNodeID<Self>) -> Result<Option<Self>, Error> {
+        Calls::record(&ctx, 1);
+        if let (Some(selections), Some(selection)) =
+            (ctx.get::<Selections>(), ctx.get::<RelayNodeSelection>())
+        {
+            selections.0.lock().unwrap().push(selection.clone());
+        }
+        Ok(Some(Post::new(id)))
+    }
+
+    async fn get_many(
+        ctx: RelayContext,
+        ids: Vec<RelayNodeID<Self>>,
+    ) -> Result<Vec<Option<Self>>, Error> {
+        Calls::record(&ctx, ids.len());
+        Ok(ids.into_iter().map(|id| Some(Post::new(id))).collect())
+    }
+}
+
+#[derive(Debug, Clone, Interface, RelayInterface)]
+#[graphql(field(name = "id", ty = "NodeGlobalID"))]
+#[relay(cache)]
+pub enum Node {
+    Post(Post),
+}
+
+fn cache_query() -> String {
+    format!(
+        r#"{{ a: node(id: "{id}") {{ ... on Post {{ title }} }} b: node(id: "{id}") {{ ... on Post {{ title }} }} c: node(id: "{id}") {{ ... on Post {{ title body }} }} }}"#,
+        id = POST_ID
+    )
+}
+
+#[tokio::test]
+async fn cache_is_keyed_by_the_selection() {
+    let calls = Calls::default();
+    let schema = Schema::build(
+        RelayNodeQuery::<Node>::new(),
+        EmptyMutation,
+        EmptySubscription,
+    )
+    .data(RelayContext::new(calls.clone()))
+    .finish();
+
+    let response = schema
+        .execute(Request::new(cache_query()).data(RelayNodeCache::new()))
+        .await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(
+        response.data,
+        value!({
+            "a": { "title": "Hello" },
+            "b": { "title": "Hello" },
+            "c": { "title": "Hello", "body": "World" },
+        })
+    );
+    assert_eq!(
+        calls.take(),
+        vec![fields(&["body", "title"], 1), fields(&["title"], 1)]
+    );
+}
+
+#[tokio::test]
+async fn with_context_passes_the_selection() {
+    let calls = Calls::default();
+    let mut relay_ctx = RelayContext::new(calls.clone());
+    relay_ctx.insert(RelayNodeCache::new());
+    let query = RelayNodeQuery::<Node>::with_context(relay_ctx);
+    let schema = Schema::build(query, EmptyMutation, EmptySubscription).finish();
+
+    let response = schema.execute(cache_query()).await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(
+        calls.take(),
+        vec![fields(&["body", "title"], 1), fields(&["title"], 1)]
+    );
+
+    let query = format!(
+        r#"{{ nodes(ids: ["{id}", "{other}"]) {{ ... on Post {{ body }} }} }}"#,
+        id = POST_ID,
+        other = OTHER_POST_ID
+    );
+    let response = schema.execute(query).await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(
+        response.data,
+        value!({ "nodes": [{ "body": "World" }, { "body": "World" }] })
+    );
+    assert_eq!(calls.take(), vec![fields(&["body"], 2)]);
+}
+
+#[tokio::test]
+async fn fragments_selecting_the_same_field_are_merged() {
+    let selections = Selections::default();
+    let schema = Schema::build(
+        RelayNodeQuery::<Node>::new(),
+        EmptyMutation,
+        EmptySubscription,
+    )
+    .data(RelayContext::new(selections.clone()))
+    .finish();
+
+    let query = format!(
+        r#"{{ node(id: "{id}") {{ ... on Post {{ author {{ profile {{ name }} }} }} ... on Post {{ author {{ profile {{ bio }} }} }} }} }}"#,
+        id = POST_ID
+    );
+    let response = schema.execute(query).await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+
+    let selections = selections.0.lock().unwrap();
+    let profile = selections[0]
+        .field("author")
+        .and_then(|author| author.field("profile"))
+        .unwrap();
+    let mut fields = profile.fields().collect::<Vec<_>>();
+    fields.sort_unstable();
+    assert_eq!(fields, vec!["bio", "name"]);
+}
+
+#[cfg(feature = "dataloader")]
+mod dataloader {
+    use async_graphql::dataloader::DataLoader;
+    use async_graphql_relay::RelayNodeLoader;
+
+    use super::*;
+
+    #[derive(Debug, Clone, Interface, RelayInterface)]
+    #[graphql(field(name = "id", ty = "LoadedNodeGlobalID"))]
+    #[relay(dataloader)]
+    pub enum LoadedNode {
+        Post(Post),
+    }
+
+    #[tokio::test]
+    async fn loader_is_keyed_by_the_selection() {
+        let calls = Calls::default();
+        let loader = DataLoader::new(
+            RelayNodeLoader::new(RelayContext::new(calls.clone())),
+            tokio::spawn,
+        );
+        let schema = Schema::build(
+            RelayNodeQuery::<LoadedNode>::new(),
+            EmptyMutation,
+            EmptySubscription,
+        )
+        .data(loader)
+        .finish();
+
+        let query = format!(
+            r#"{{ a: node(id: "{id}") {{ ... on Post {{ title }} }} b: node(id: "{other}") {{ ... on Post {{ title }} }} c: node(id: "{id}") {{ ... on Post {{ title body }} }} }}"#,
+            id = POST_ID,
+            other = OTHER_POST_ID
+        );
+        let response = schema.execute(query).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response.data,
+            value!({
+                "a": { "title": "Hello" },
+                "b": { "title": "Hello" },
+                "c": { "title": "Hello", "body": "World" },
+            })
+        );
+        assert_eq!(
+            calls.take(),
+            vec![fields(&["body", "title"], 1), fields(&["title"], 2)]
+        );
+    }
+}
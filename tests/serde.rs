@@ -0,0 +1,121 @@
+#![cfg(feature = "serde")]
+
+use std::convert::TryFrom;
+
+use async_graphql::{Error, Interface, SimpleObject};
+use async_graphql_relay::{RelayContext, RelayInterface, RelayNode, RelayNodeID, RelayNodeObject};
+use serde::{Deserialize, Serialize};
+
+const USER_ID: &str = "92ba0c2d4b4e4e2991dd8f96a078c3ffu";
+
+#[derive(Debug, Clone, SimpleObject, RelayNodeObject)]
+#[relay(node_suffix = "u")]
+pub struct User {
+    pub id: RelayNodeID<User>,
+}
+
+impl RelayNode for User {
+    async fn get(_ctx: RelayContext, _id: RelayNodeID<Self>) -> Result<Option<Self>, Error> {
+        Ok(None)
+    }
+}
+
+#[derive(Debug, Clone, SimpleObject, RelayNodeObject)]
+#[relay(node_suffix = "p")]
+pub struct Post {
+    pub id: RelayNodeID<Post>,
+}
+
+impl RelayNode for Post {
+    async fn get(_ctx: RelayContext, _id: RelayNodeID<Self>) -> Result<Option<Self>, Error> {
+        Ok(None)
+    }
+}
+
+#[derive(Debug, Interface, RelayInterface)]
+#[graphql(field(name = "id", ty = "NodeGlobalID"))]
+pub enum Node {
+    User(User),
+    Post(Post),
+}
+
+fn user_id() -> RelayNodeID<User> {
+    RelayNodeID::new_from_relay_id(USER_ID.to_string()).unwrap()
+}
+
+fn json(relay_id: &str) -> String {
+    format!("\"{}\"", relay_id)
+}
+
+#[test]
+fn relay_node_id_round_trip() {
+    let serialized = serde_json::to_string(&user_id()).unwrap();
+    assert_eq!(serialized, json(USER_ID));
+    let id: RelayNodeID<User> = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(id, user_id());
+}
+
+#[test]
+fn relay_node_id_rejects_invalid_ids() {
+    let err = serde_json::from_str::<RelayNodeID<Post>>(&json(USER_ID)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Expected an id of type 'p' but got an id of type 'u'!"
+    );
+    let err = serde_json::from_str::<RelayNodeID<User>>(&json("invalid")).unwrap_err();
+    assert_eq!(err.to_string(), "Invalid id provided to node query!");
+    assert!(serde_json::from_str::<RelayNodeID<User>>("42").is_err());
+}
+
+#[test]
+fn global_and_typed_id_round_trip() {
+    let global_id = NodeGlobalID::from(&user_id());
+    let serialized = serde_json::to_string(&global_id).unwrap();
+    assert_eq!(serialized, json(USER_ID));
+    let global_id: NodeGlobalID = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(
+        NodeTypedID::try_from(global_id),
+        Ok(NodeTypedID::User(user_id()))
+    );
+
+    let typed_id = NodeTypedID::User(user_id());
+    let serialized = serde_json::to_string(&typed_id).unwrap();
+    assert_eq!(serialized, json(USER_ID));
+    let deserialized: NodeTypedID = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, typed_id);
+}
+
+#[test]
+fn global_and_typed_id_reject_invalid_ids() {
+    let unknown = json("92ba0c2d4b4e4e2991dd8f96a078c3ffx");
+    let err = serde_json::from_str::<NodeGlobalID>(&unknown).unwrap_err();
+    assert_eq!(err.to_string(), "Unknown type 'x' in the provided id!");
+    assert!(serde_json::from_str::<NodeTypedID>(&unknown).is_err());
+
+    let err = serde_json::from_str::<NodeGlobalID>(&json("invalid")).unwrap_err();
+    assert_eq!(err.to_string(), "Invalid id provided to node query!");
+    assert!(serde_json::from_str::<NodeTypedID>(&json("invalid")).is_err());
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct SendWelcomeEmail {
+    #[serde(with = "async_graphql_relay::serde_key")]
+    user_id: RelayNodeID<User>,
+}
+
+#[test]
+fn serde_key_round_trip() {
+    let job = SendWelcomeEmail { user_id: user_id() };
+    let serialized = serde_json::to_string(&job).unwrap();
+    assert_eq!(
+        serialized,
+        r#"{"user_id":"92ba0c2d-4b4e-4e29-91dd-8f96a078c3ff"}"#
+    );
+    let deserialized: SendWelcomeEmail = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, job);
+
+    assert!(
+        serde_json::from_str::<SendWelcomeEmail>(&format!(r#"{{"user_id":"{}"}}"#, USER_ID))
+            .is_err()
+    );
+}
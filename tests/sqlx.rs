@@ -0,0 +1,76 @@
+#![cfg(feature = "sqlx")]
+
+use async_graphql::Error;
+use async_graphql_relay::{RelayContext, RelayNode, RelayNodeID, RelayNodeObject};
+use sqlx::{sqlite::SqlitePool, FromRow};
+
+#[derive(RelayNodeObject)]
+#[relay(node_suffix = "u")]
+pub struct User;
+
+impl RelayNode for User {
+    async fn get(_ctx: RelayContext, _id: RelayNodeID<Self>) -> Result<Option<Self>, Error> {
+        Ok(None)
+    }
+}
+
+#[derive(RelayNodeObject)]
+#[relay(node_suffix = "p", key = "i64")]
+pub struct Post;
+
+impl RelayNode for Post {
+    async fn get(_ctx: RelayContext, _id: RelayNodeID<Self>) -> Result<Option<Self>, Error> {
+        Ok(None)
+    }
+}
+
+#[derive(FromRow)]
+struct PostRow {
+    id: RelayNodeID<Post>,
+    author_id: RelayNodeID<User>,
+    title: String,
+}
+
+async fn pool() -> SqlitePool {
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    sqlx::query(
+        "CREATE TABLE posts (id INTEGER PRIMARY KEY, author_id BLOB NOT NULL, title TEXT NOT NULL)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    pool
+}
+
+#[tokio::test]
+async fn bind_and_decode() {
+    let pool = pool().await;
+    let id = RelayNodeID::<Post>::new(42);
+    let author_id =
+        RelayNodeID::<User>::new_from_str("92ba0c2d-4b4e-4e29-91dd-8f96a078c3ff").unwrap();
+
+    sqlx::query("INSERT INTO posts (id, author_id, title) VALUES (?, ?, ?)")
+        .bind(&id)
+        .bind(&author_id)
+        .bind("Hello")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let (decoded,): (RelayNodeID<Post>,) =
+        sqlx::query_as("SELECT id FROM posts WHERE author_id = ?")
+            .bind(&author_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(decoded, id);
+
+    let row: PostRow = sqlx::query_as("SELECT id, author_id, title FROM posts WHERE id = ?")
+        .bind(&id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(row.id, id);
+    assert_eq!(row.author_id, author_id);
+    assert_eq!(row.title, "Hello");
+}
@@ -0,0 +1,87 @@
+use async_graphql::{
+    value, EmptyMutation, EmptySubscription, Error, Interface, MergedObject, Object, Request,
+    Schema, SimpleObject,
+};
+use async_graphql_relay::{
+    RelayContext, RelayInterface, RelayNode, RelayNodeCache, RelayNodeID, RelayNodeObject,
+    RelayNodeQuery, RelayStats,
+};
+
+const USER_ID: &str = "92ba0c2d4b4e4e2991dd8f96a078c3ffu";
+const MISSING_ID: &str = "00000000000000000000000000000000u";
+const FAILING_ID: &str = "ffffffffffffffffffffffffffffffffu";
+
+#[derive(Debug, Clone, SimpleObject, RelayNodeObject)]
+#[relay(node_suffix = "u")]
+pub struct User {
+    pub id: RelayNodeID<User>,
+}
+
+impl RelayNode for User {
+    async fn get(_ctx: RelayContext, id: RelayNodeID<Self>) -> Result<Option<Self>, Error> {
+        let uuid = id.to_uuid();
+        if uuid.is_nil() {
+            Ok(None)
+        } else if uuid.is_max() {
+            Err(Error::new("The database is unavailable!"))
+        } else {
+            Ok(Some(User { id }))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Interface, RelayInterface)]
+#[graphql(field(name = "id", ty = "NodeGlobalID"))]
+#[relay(cache)]
+pub enum Node {
+    User(User),
+}
+
+#[derive(Default)]
+pub struct Query;
+
+#[Object]
+impl Query {
+    async fn version(&self) -> i32 {
+        1
+    }
+}
+
+#[derive(MergedObject, Default)]
+pub struct QueryRoot(Query, RelayNodeQuery<Node>);
+
+fn schema() -> Schema<QueryRoot, EmptyMutation, EmptySubscription> {
+    Schema::build(QueryRoot::default(), EmptyMutation, EmptySubscription)
+        .extension(RelayStats)
+        .finish()
+}
+
+#[tokio::test]
+async fn counts_the_nodes_fetched_by_node_and_nodes() {
+    let query = format!(
+        r#"{{ a: node(id: "{user}") {{ id }} b: node(id: "{user}") {{ id }} c: node(id: "{missing}") {{ id }} d: node(id: "{failing}") {{ id }} nodes(ids: ["{user}", "{missing}"]) {{ id }} }}"#,
+        user = USER_ID,
+        missing = MISSING_ID,
+        failing = FAILING_ID
+    );
+    let response = schema()
+        .execute(Request::new(query).data(RelayNodeCache::new()))
+        .await;
+    assert_eq!(response.errors.len(), 1, "{:?}", response.errors);
+    assert_eq!(
+        response.extensions.get("relay"),
+        Some(&value!({
+            "cacheHits": 1,
+            "types": {
+                "User": { "fetches": 5, "notFound": 2, "errors": 1 },
+            },
+        }))
+    );
+}
+
+#[tokio::test]
+async fn leaves_out_the_stats_when_no_nodes_are_fetched() {
+    let response = schema().execute("{ version }").await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(response.extensions.get("relay"), None);
+}
@@ -0,0 +1,195 @@
+#![cfg(feature = "tracing")]
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use async_graphql::{
+    EmptyMutation, EmptySubscription, Error, Interface, Schema, SchemaBuilder, SimpleObject,
+};
+use async_graphql_relay::{
+    RelayContext, RelayInterface, RelayNode, RelayNodeID, RelayNodeObject, RelayNodeQuery,
+    RelayTracing,
+};
+use tracing::{
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+    Event, Metadata, Subscriber,
+};
+
+const USER_ID: &str = "92ba0c2d4b4e4e2991dd8f96a078c3ffu";
+const MISSING_ID: &str = "00000000000000000000000000000000u";
+
+#[derive(Debug, Clone, SimpleObject, RelayNodeObject)]
+#[relay(node_suffix = "u")]
+pub struct User {
+    pub id: RelayNodeID<User>,
+}
+
+impl RelayNode for User {
+    async fn get(_ctx: RelayContext, id: RelayNodeID<Self>) -> Result<Option<Self>, Error> {
+        Ok(match id.to_uuid().is_nil() {
+            true => None,
+            false => Some(User { id }),
+        })
+    }
+}
+
+#[derive(Debug, Interface, RelayInterface)]
+#[graphql(field(name = "id", ty = "NodeGlobalID"))]
+pub enum Node {
+    User(User),
+}
+
+/// Fields collects the fields of a span or event as strings.
+#[derive(Default)]
+struct Fields(BTreeMap<String, String>);
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{:?}", value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+}
+
+/// Captured is a span or event recorded by the 'Capture' subscriber.
+#[derive(Debug, Clone, PartialEq)]
+struct Captured {
+    name: String,
+    fields: BTreeMap<String, String>,
+}
+
+/// Capture is a subscriber which records every span and event.
+#[derive(Clone, Default)]
+struct Capture {
+    next_id: Arc<AtomicU64>,
+    spans: Arc<Mutex<Vec<Captured>>>,
+    events: Arc<Mutex<Vec<Captured>>>,
+}
+
+impl Subscriber for Capture {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut fields = Fields::default();
+        span.record(&mut fields);
+        self.spans.lock().unwrap().push(Captured {
+            name: span.metadata().name().to_string(),
+            fields: fields.0,
+        });
+        Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        let name = fields.0.remove("message").unwrap_or_default();
+        self.events.lock().unwrap().push(Captured {
+            name,
+            fields: fields.0,
+        });
+    }
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+impl Capture {
+    fn span(&self, name: &str) -> Captured {
+        let spans = self.spans.lock().unwrap();
+        spans
+            .iter()
+            .find(|span| span.name == name)
+            .cloned()
+            .unwrap_or_else(|| panic!("No '{}' span in {:?}", name, spans))
+    }
+
+    fn event(&self, name: &str) -> Captured {
+        let events = self.events.lock().unwrap();
+        events
+            .iter()
+            .find(|event| event.name == name)
+            .cloned()
+            .unwrap_or_else(|| panic!("No '{}' event in {:?}", name, events))
+    }
+}
+
+type NodeSchema = Schema<RelayNodeQuery<Node>, EmptyMutation, EmptySubscription>;
+
+fn schema() -> SchemaBuilder<RelayNodeQuery<Node>, EmptyMutation, EmptySubscription> {
+    Schema::build(RelayNodeQuery::new(), EmptyMutation, EmptySubscription).data(RelayContext::nil())
+}
+
+async fn capture(schema: &NodeSchema, relay_id: &str) -> Capture {
+    let capture = Capture::default();
+    let _guard = tracing::subscriber::set_default(capture.clone());
+    let query = format!(r#"{{ node(id: "{}") {{ id }} }}"#, relay_id);
+    schema.execute(query).await;
+    capture
+}
+
+fn fields(fields: &[(&str, &str)]) -> BTreeMap<String, String> {
+    fields
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+#[tokio::test]
+async fn ids_are_redacted_by_default() {
+    let capture = capture(&schema().finish(), USER_ID).await;
+    assert_eq!(
+        capture.span("relay.fetch_node").fields,
+        fields(&[("relay.id", "[redacted]")])
+    );
+    assert_eq!(
+        capture.span("relay.get").fields,
+        fields(&[("relay.id", "[redacted]"), ("relay.type", "User")])
+    );
+}
+
+#[tokio::test]
+async fn ids_are_recorded_with_the_extension() {
+    let schema = schema().extension(RelayTracing::new().with_ids()).finish();
+    let capture = capture(&schema, USER_ID).await;
+    assert_eq!(
+        capture.span("relay.fetch_node").fields,
+        fields(&[("relay.id", USER_ID)])
+    );
+    assert_eq!(
+        capture.span("relay.get").fields,
+        fields(&[("relay.id", USER_ID), ("relay.type", "User")])
+    );
+}
+
+#[tokio::test]
+async fn missing_nodes_and_invalid_ids_are_recorded_as_events() {
+    let schema = schema().finish();
+    let capture_missing = capture(&schema, MISSING_ID).await;
+    capture_missing.event("relay node not found");
+
+    let capture_invalid = capture(&schema, "invalid").await;
+    assert_eq!(
+        capture_invalid.event("invalid relay id").fields,
+        fields(&[
+            ("code", "INVALID_FORMAT"),
+            ("error", "Invalid id provided to node query!")
+        ])
+    );
+}